@@ -4,17 +4,140 @@ use assembly_fdb::mem::Field;
 use latin1str::Latin1Str;
 
 use crate::{
-    columns::{ItemSetsColumn, SkillBehaviorColumn},
-    tables::{ItemSetsTable, MissionTasksTable, ObjectsTable, SkillBehaviorTable},
-    TypedTable,
+    columns::ItemSetsColumn,
+    rows::ItemComponentRow,
+    tables::{
+        CurrencyTableTable, ItemSetsTable, MissionTasksTable, ObjectsTable, SkillBehaviorTable,
+    },
+    TypedDatabase, TypedTable,
 };
 use serde::Serialize;
 
+pub mod activities;
+pub mod behaviors;
+pub mod buffs;
+pub mod expr;
+pub mod factions;
+pub mod items;
+pub mod localization;
+pub mod loot;
+pub mod mission_detail;
+pub mod mission_graph;
+pub mod missions;
+pub mod pets;
+pub mod preconditions;
+pub mod progression;
+pub mod quickbuilds;
+pub mod skills;
+pub mod smashables;
+pub mod speedchat;
+pub mod texts;
+pub mod vendors;
+pub mod world_config;
+pub mod zones;
+
+#[cfg(test)]
+mod test_util;
+
+/// Parse a list of IDs separated by `,` or `;`, skipping invalid entries
+pub(crate) fn parse_id_list(text: &Latin1Str) -> Vec<i32> {
+    text.decode()
+        .split(&[',', ';'][..])
+        .map(str::trim)
+        .filter_map(|idstr| idstr.parse::<i32>().ok())
+        .collect()
+}
+
 /// Well-known components of an object
 #[derive(Debug, Copy, Clone, Default)]
 pub struct Components {
     /// The render component of the object
     pub render: Option<i32>,
+    /// The destructible component of the object
+    pub destructible: Option<i32>,
+    /// The item component of the object
+    pub item: Option<i32>,
+    /// The vendor component of the object
+    pub vendor: Option<i32>,
+    /// The inventory component of the object
+    pub inventory: Option<i32>,
+    /// The pet component of the object
+    pub pet: Option<i32>,
+    /// The quickbuild component of the object
+    pub quickbuild: Option<i32>,
+    /// The choice build component of the object
+    pub choice_build: Option<i32>,
+}
+
+/// A range of coins that may be dropped
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub struct CurrencyRange {
+    /// The minimum number of coins
+    pub min: i32,
+    /// The maximum number of coins
+    pub max: i32,
+}
+
+impl CurrencyRange {
+    /// Check whether this range never drops any coins
+    pub fn is_empty(&self) -> bool {
+        self.max <= 0
+    }
+
+    /// Pick a random amount of coins from this range
+    #[cfg(feature = "rand")]
+    pub fn roll<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> i32 {
+        if self.min >= self.max {
+            self.min
+        } else {
+            rng.gen_range(self.min..=self.max)
+        }
+    }
+}
+
+impl<'db> CurrencyTableTable<'db> {
+    /// Get the coin range for a currency index and NPC level
+    ///
+    /// This picks the entry with the highest `npcminlevel` that is
+    /// less than or equal to `level`.
+    pub fn get_range(&self, currency_index: i32, level: i32) -> Option<CurrencyRange> {
+        let rows = self.key_iter(currency_index).map(|row| {
+            let range = CurrencyRange {
+                min: row.minvalue(),
+                max: row.maxvalue(),
+            };
+            (row.npcminlevel(), range)
+        });
+        range_for_level(rows, level)
+    }
+}
+
+/// Pick the range with the highest minimum level that is at most `level`
+fn range_for_level<I>(rows: I, level: i32) -> Option<CurrencyRange>
+where
+    I: IntoIterator<Item = (i32, CurrencyRange)>,
+{
+    rows.into_iter()
+        .filter(|&(min_level, _)| min_level <= level)
+        .max_by_key(|&(min_level, _)| min_level)
+        .map(|(_, range)| range)
+}
+
+impl<'db> TypedDatabase<'db> {
+    /// Get the item component of an object
+    pub fn get_item_component(&self, lot: i32) -> Option<ItemComponentRow<'_, '_>> {
+        let id = self.get_components(lot).item?;
+        self.item_component.key_iter(id).next()
+    }
+
+    /// Get the coin range for a `CurrencyIndex` at the given NPC level
+    ///
+    /// Returns `None` if the `CurrencyTable` is missing or has no matching entry.
+    pub fn currency_range(&self, currency_index: i32, level: i32) -> Option<CurrencyRange> {
+        self.currency_table
+            .as_ref()?
+            .get_range(currency_index, level)
+    }
 }
 
 /// Data for an item set
@@ -28,6 +151,8 @@ pub struct ItemSet {
     pub kit_rank: i32,
     /// The ID of the Image used to present the set
     pub kit_image: Option<i32>,
+    /// The `ItemSetSkills` groups by number of equipped items (`skillSetWith2`..`skillSetWith6`)
+    pub skill_sets: Vec<(usize, i32)>,
 }
 
 impl<'db> ItemSetsTable<'db> {
@@ -48,6 +173,13 @@ impl<'db> ItemSetsTable<'db> {
         let col_kit_rank = self
             .get_col(ItemSetsColumn::KitRank)
             .expect("Missing column 'ItemSets::kitRank'");
+        let col_skill_sets = [
+            (2, self.get_col(ItemSetsColumn::SkillSetWith2)),
+            (3, self.get_col(ItemSetsColumn::SkillSetWith3)),
+            (4, self.get_col(ItemSetsColumn::SkillSetWith4)),
+            (5, self.get_col(ItemSetsColumn::SkillSetWith5)),
+            (6, self.get_col(ItemSetsColumn::SkillSetWith6)),
+        ];
 
         for row in bucket.row_iter() {
             let id_field = row.field_at(0).unwrap();
@@ -64,22 +196,22 @@ impl<'db> ItemSetsTable<'db> {
                     .into_opt_integer()
                     .unwrap_or(0);
                 let kit_image = row.field_at(col_kit_image).unwrap().into_opt_integer();
-                let item_ids = row
-                    .field_at(col_item_ids)
-                    .unwrap()
-                    .into_opt_text()
-                    .unwrap()
-                    .decode()
-                    .split(',')
-                    .map(str::trim)
-                    .filter_map(|idstr| idstr.parse::<i32>().ok())
+                let skill_sets = col_skill_sets
+                    .iter()
+                    .filter_map(|&(count, col)| {
+                        let id = row.field_at(col?)?.into_opt_integer()?;
+                        Some((count, id))
+                    })
                     .collect();
+                let item_ids =
+                    parse_id_list(row.field_at(col_item_ids).unwrap().into_opt_text().unwrap());
 
                 return Some(ItemSet {
                     kit_type,
                     kit_rank,
                     kit_image,
                     item_ids,
+                    skill_sets,
                 });
             }
         }
@@ -96,6 +228,13 @@ pub struct Mission {
     pub is_mission: bool,
 }
 
+impl Mission {
+    /// Get the kind of this entry
+    pub fn kind(&self) -> MissionKind {
+        MissionKind::from_is_mission(self.is_mission)
+    }
+}
+
 #[derive(Default)]
 /// Data for a mission task
 pub struct MissionTask {
@@ -105,7 +244,7 @@ pub struct MissionTask {
     pub uid: i32,
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
 /// The kind of an entry in the `Missions` table
 pub enum MissionKind {
     /// The entry is an achievement (i.e. is active by default)
@@ -114,6 +253,17 @@ pub enum MissionKind {
     Mission,
 }
 
+impl MissionKind {
+    /// Get the kind from the `isMission` column
+    pub fn from_is_mission(is_mission: bool) -> Self {
+        if is_mission {
+            Self::Mission
+        } else {
+            Self::Achievement
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, Serialize)]
 /// Metadata for an object
 pub struct ObjectRef<'a> {
@@ -133,33 +283,36 @@ impl<'a> ObjectsTable<'a> {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 /// Data for a skill
 pub struct SkillBehavior {
     /// The icon of the skill
     pub skill_icon: Option<i32>,
+    /// The root behavior of the skill
+    pub behavior_id: i32,
+    /// The imagination cost of the skill
+    pub imagination_cost: i32,
+    /// The cooldown of the skill in seconds
+    pub cooldown: f32,
+    /// The cooldown group of the skill
+    pub cooldown_group: Option<i32>,
+    /// The cast type of the skill (`castTypeDesc`)
+    pub cast_type: Option<i32>,
 }
 
 impl<'db> SkillBehaviorTable<'db> {
     /// Get the data for a skill
     pub fn get_data(&self, id: i32) -> Option<SkillBehavior> {
-        let hash = u32::from_ne_bytes(id.to_ne_bytes());
-        let bucket = self.as_raw().bucket_for_hash(hash);
-
-        let col_skill_icon = self
-            .get_col(SkillBehaviorColumn::SkillIcon)
-            .expect("Missing 'SkillBehavior::skillIcon'");
-
-        for row in bucket.row_iter() {
-            let id_field = row.field_at(0).unwrap();
-
-            if id_field == Field::Integer(id) {
-                let skill_icon = row.field_at(col_skill_icon).unwrap().into_opt_integer();
-
-                return Some(SkillBehavior { skill_icon });
-            }
-        }
-        None
+        let row = self.key_iter(id).next()?;
+        Some(SkillBehavior {
+            skill_icon: row.skill_icon(),
+            behavior_id: row.behavior_id(),
+            imagination_cost: row.imaginationcost(),
+            cooldown: row.cooldown(),
+            cooldown_group: row.cooldowngroup(),
+            cast_type: row.cast_type_desc(),
+        })
     }
 }
 
@@ -182,3 +335,47 @@ impl<'a> MissionTasksTable<'a> {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{range_for_level, CurrencyRange};
+
+    const ROWS: [(i32, CurrencyRange); 3] = [
+        (5, CurrencyRange { min: 5, max: 10 }),
+        (1, CurrencyRange { min: 1, max: 2 }),
+        (10, CurrencyRange { min: 20, max: 30 }),
+    ];
+
+    #[test]
+    fn test_range_for_level() {
+        let range = |level| range_for_level(ROWS.iter().copied(), level).map(|r| r.min);
+        assert_eq!(range(0), None);
+        assert_eq!(range(1), Some(1));
+        assert_eq!(range(4), Some(1));
+        assert_eq!(range(5), Some(5));
+        assert_eq!(range(10), Some(20));
+        assert_eq!(range(50), Some(20));
+        assert_eq!(range_for_level(Vec::new(), 10), None);
+    }
+
+    #[test]
+    fn test_is_empty() {
+        assert!(CurrencyRange { min: 0, max: 0 }.is_empty());
+        assert!(!CurrencyRange { min: 0, max: 1 }.is_empty());
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_roll_bounds() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let range = CurrencyRange { min: 3, max: 5 };
+        for _ in 0..100 {
+            let coins = range.roll(&mut rng);
+            assert!((3..=5).contains(&coins));
+        }
+        assert_eq!(CurrencyRange { min: 4, max: 4 }.roll(&mut rng), 4);
+        assert_eq!(CurrencyRange { min: 6, max: 2 }.roll(&mut rng), 6);
+    }
+}
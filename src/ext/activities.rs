@@ -0,0 +1,168 @@
+//! # Activities
+//!
+//! `Activities` has one row per minigame or other scored activity. The keys of
+//! its localized texts are listed in `ActivityText`, and the rewards for every
+//! rating tier are in `ActivityRewards`, where `objectTemplate` is the activity ID.
+
+use std::time::Duration;
+
+use serde::Serialize;
+
+use super::{loot::LootMatrixEntry, CurrencyRange};
+use crate::TypedDatabase;
+
+/// The kind of leaderboard of an activity
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub enum LeaderboardType {
+    /// Shooting gallery scores
+    ShootingGallery,
+    /// Race times
+    Racing,
+    /// Monument race times
+    MonumentRace,
+    /// Foot race times
+    FootRace,
+    /// Survival scores
+    Survival,
+    /// Survival scores (Nexus Tower)
+    SurvivalNs,
+    /// Donation totals
+    Donations,
+    /// Any other value
+    Unknown(i32),
+}
+
+impl From<i32> for LeaderboardType {
+    fn from(value: i32) -> Self {
+        match value {
+            0 => Self::ShootingGallery,
+            1 => Self::Racing,
+            2 => Self::MonumentRace,
+            3 => Self::FootRace,
+            5 => Self::Survival,
+            6 => Self::SurvivalNs,
+            7 => Self::Donations,
+            _ => Self::Unknown(value),
+        }
+    }
+}
+
+/// A reward tier of an activity
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityReward {
+    /// `ActivityRewardIndex`
+    pub index: i32,
+    /// The rating needed for this tier
+    pub activity_rating: i32,
+    /// `ChallengeRating`
+    pub challenge_rating: i32,
+    /// The description of the tier
+    pub description: String,
+    /// The loot matrix of the tier
+    pub loot_matrix_index: Option<i32>,
+    /// The entries of the loot matrix
+    pub loot: Vec<LootMatrixEntry>,
+    /// The currency index of the tier
+    pub currency_index: Option<i32>,
+    /// The coins granted by the tier
+    pub currency: Option<CurrencyRange>,
+}
+
+/// The data of an activity
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Activity {
+    /// The ID of the activity
+    pub id: i32,
+    /// The zone the activity is played in
+    pub instance_map_id: Option<i32>,
+    /// `minTeams`
+    pub min_teams: Option<i32>,
+    /// `maxTeams`
+    pub max_teams: Option<i32>,
+    /// `minTeamSize`
+    pub min_team_size: Option<i32>,
+    /// `maxTeamSize`
+    pub max_team_size: Option<i32>,
+    /// The time to wait for more players (`waitTime`)
+    pub wait_time: Option<Duration>,
+    /// The delay before the activity starts (`startDelay`)
+    pub start_delay: Option<Duration>,
+    /// The kind of leaderboard
+    pub leaderboard_type: Option<LeaderboardType>,
+    /// The LOT of the item to pay to play
+    pub optional_cost_lot: Option<i32>,
+    /// The number of items to pay to play
+    pub optional_cost_count: Option<i32>,
+    /// Whether rewards are shown in the UI
+    pub show_ui_rewards: bool,
+    /// The player flag set for community activities
+    pub community_activity_flag_id: Option<i32>,
+    /// The `type` keys of the localized texts in `ActivityText`
+    pub text_keys: Vec<String>,
+    /// The reward tiers, ordered by `ActivityRewardIndex`
+    pub rewards: Vec<ActivityReward>,
+}
+
+fn millis(value: i32) -> Duration {
+    Duration::from_millis(value.max(0) as u64)
+}
+
+impl<'db> TypedDatabase<'db> {
+    /// Get the reward tiers of an activity
+    ///
+    /// The coin ranges use the `CurrencyTable` entries for level 1.
+    pub fn get_activity_rewards(&self, activity_id: i32) -> Vec<ActivityReward> {
+        let table = match &self.activity_rewards {
+            Some(table) => table,
+            None => return Vec::new(),
+        };
+        let mut rewards: Vec<ActivityReward> = table
+            .key_iter(activity_id)
+            .map(|row| ActivityReward {
+                index: row.activity_reward_index(),
+                activity_rating: row.activity_rating(),
+                challenge_rating: row.challenge_rating(),
+                description: row.description().decode().into_owned(),
+                loot_matrix_index: row.loot_matrix_index(),
+                loot: row
+                    .loot_matrix_index()
+                    .map(|index| self.get_loot_matrix(index))
+                    .unwrap_or_default(),
+                currency_index: row.currency_index(),
+                currency: row
+                    .currency_index()
+                    .and_then(|index| self.currency_range(index, 1)),
+            })
+            .collect();
+        rewards.sort_by_key(|reward| reward.index);
+        rewards
+    }
+
+    /// Get the combined data of an activity
+    pub fn get_activity(&self, id: i32) -> Option<Activity> {
+        let row = self.activities.key_iter(id).next()?;
+        Some(Activity {
+            id,
+            instance_map_id: row.instance_map_id(),
+            min_teams: row.min_teams(),
+            max_teams: row.max_teams(),
+            min_team_size: row.min_team_size(),
+            max_team_size: row.max_team_size(),
+            wait_time: row.wait_time().map(millis),
+            start_delay: row.start_delay().map(millis),
+            leaderboard_type: row.leaderboard_type().map(LeaderboardType::from),
+            optional_cost_lot: row.optional_cost_lot(),
+            optional_cost_count: row.optional_cost_count(),
+            show_ui_rewards: row.show_ui_rewards(),
+            community_activity_flag_id: row.community_activity_flag_id(),
+            text_keys: self
+                .activity_text
+                .key_iter(id)
+                .map(|text| text.r#type().decode().into_owned())
+                .collect(),
+            rewards: self.get_activity_rewards(id),
+        })
+    }
+}
@@ -0,0 +1,731 @@
+//! # Behavior trees
+//!
+//! Every skill in `SkillBehavior` references a root behavior. Each behavior has a
+//! template in `BehaviorTemplate` and a set of named parameters in `BehaviorParameter`.
+//! Some of these parameters are the IDs of other behaviors, which turns the
+//! behaviors of a skill into a tree (or, for some broken entries, a graph with cycles).
+//!
+//! For well-known templates, [`Behavior`] turns the parameters into named fields.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::TypedDatabase;
+
+/// Check whether a parameter name may refer to another behavior
+///
+/// The value still needs to be a valid behavior ID.
+pub fn is_behavior_param(name: &str) -> bool {
+    name.contains("action")
+        || name.starts_with("behavior")
+        || name.starts_with("on_")
+        || name == "miss"
+        || name == "blocked"
+}
+
+/// A single behavior with its template and parameters
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BehaviorNode {
+    /// The ID of the behavior
+    pub id: i32,
+    /// The template of the behavior
+    pub template_id: i32,
+    /// The name of the template, from `BehaviorTemplateName`
+    pub template_name: Option<String>,
+    /// The effect of the behavior
+    pub effect_id: i32,
+    /// The effect handle of the behavior
+    pub effect_handle: Option<String>,
+    /// All parameters, including the ones that link to other behaviors
+    pub parameters: BTreeMap<String, f32>,
+    /// The parameters that link to other behaviors
+    ///
+    /// The IDs are not checked, see [`BehaviorGraph::missing`].
+    pub children: BTreeMap<String, i32>,
+}
+
+impl BehaviorNode {
+    /// Get a parameter by name
+    pub fn param(&self, name: &str) -> Option<f32> {
+        self.parameters.get(name).copied()
+    }
+
+    /// Get a child behavior by parameter name
+    pub fn child(&self, name: &str) -> Option<i32> {
+        self.children.get(name).copied()
+    }
+}
+
+/// A link from a behavior to one of its ancestors
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BehaviorBackEdge {
+    /// The behavior with the parameter
+    pub from: i32,
+    /// The name of the parameter
+    pub parameter: String,
+    /// The ancestor behavior
+    pub to: i32,
+}
+
+/// All behaviors reachable from one root behavior
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BehaviorGraph {
+    /// The root behavior
+    pub root: i32,
+    /// All reachable behaviors by ID
+    pub nodes: BTreeMap<i32, BehaviorNode>,
+    /// Links that point back to an ancestor
+    pub back_edges: Vec<BehaviorBackEdge>,
+    /// Referenced behaviors that are missing from `BehaviorTemplate`
+    pub missing: BTreeSet<i32>,
+}
+
+impl BehaviorGraph {
+    /// Get a single behavior
+    pub fn get(&self, id: i32) -> Option<&BehaviorNode> {
+        self.nodes.get(&id)
+    }
+
+    /// Get the root behavior
+    pub fn root_node(&self) -> Option<&BehaviorNode> {
+        self.nodes.get(&self.root)
+    }
+
+    /// Check whether some behavior links back to one of its ancestors
+    pub fn has_cycle(&self) -> bool {
+        !self.back_edges.is_empty()
+    }
+
+    fn build<F: Fn(i32) -> Option<BehaviorNode>>(root: i32, load: F) -> Self {
+        let mut graph = BehaviorGraph {
+            root,
+            nodes: BTreeMap::new(),
+            back_edges: Vec::new(),
+            missing: BTreeSet::new(),
+        };
+        let mut path = Vec::new();
+        graph.visit(root, &mut path, &load);
+        graph
+    }
+
+    fn visit<F: Fn(i32) -> Option<BehaviorNode>>(
+        &mut self,
+        id: i32,
+        path: &mut Vec<i32>,
+        load: &F,
+    ) {
+        if self.nodes.contains_key(&id) || self.missing.contains(&id) {
+            return;
+        }
+        let node = match load(id) {
+            Some(node) => node,
+            None => {
+                self.missing.insert(id);
+                return;
+            }
+        };
+        let children: Vec<(String, i32)> =
+            node.children.iter().map(|(k, v)| (k.clone(), *v)).collect();
+        self.nodes.insert(id, node);
+        path.push(id);
+        for (parameter, child) in children {
+            if path.contains(&child) {
+                self.back_edges.push(BehaviorBackEdge {
+                    from: id,
+                    parameter,
+                    to: child,
+                });
+            } else {
+                self.visit(child, path, load);
+            }
+        }
+        path.pop();
+    }
+}
+
+impl<'db> TypedDatabase<'db> {
+    /// Load a single behavior node
+    pub fn get_behavior_node(&self, id: i32) -> Option<BehaviorNode> {
+        let template = self.behavior_templates.key_iter(id).next()?;
+        let template_id = template.template_id();
+        let template_name = self
+            .behavior_template_names
+            .as_ref()
+            .and_then(|t| t.key_iter(template_id).next())
+            .map(|row| row.name().decode().into_owned());
+
+        let mut parameters = BTreeMap::new();
+        let mut children = BTreeMap::new();
+        for row in self.behavior_parameters.key_iter(id) {
+            let name = row.parameter_id().decode().into_owned();
+            let value = row.value();
+            if is_behavior_param(&name) && value > 0.0 && value.fract() == 0.0 {
+                children.insert(name.clone(), value as i32);
+            }
+            parameters.insert(name, value);
+        }
+
+        Some(BehaviorNode {
+            id,
+            template_id,
+            template_name,
+            effect_id: template.effect_id(),
+            effect_handle: template.effect_handle().map(|s| s.decode().into_owned()),
+            parameters,
+            children,
+        })
+    }
+
+    /// Load all behaviors reachable from the given behavior
+    pub fn behavior_graph(&self, root: i32) -> BehaviorGraph {
+        BehaviorGraph::build(root, |id| self.get_behavior_node(id))
+    }
+
+    /// Load the behavior tree of a skill
+    pub fn skill_behavior_graph(&self, skill_id: i32) -> Option<BehaviorGraph> {
+        let skill = self.skills.key_iter(skill_id).next()?;
+        Some(self.behavior_graph(skill.behavior_id()))
+    }
+}
+
+/// The ID of a child behavior
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct BehaviorId(pub i32);
+
+impl BehaviorNode {
+    fn action(&self, name: &str) -> Option<BehaviorId> {
+        self.param(name)
+            .filter(|v| *v > 0.0)
+            .map(|v| BehaviorId(v as i32))
+    }
+
+    fn numbered_actions(&self, prefix: &str) -> Vec<BehaviorId> {
+        (1..)
+            .map(|i| self.action(&format!("{} {}", prefix, i)))
+            .take_while(Option::is_some)
+            .flatten()
+            .collect()
+    }
+
+    fn int(&self, name: &str) -> i32 {
+        self.param(name).unwrap_or(0.0) as i32
+    }
+
+    fn float(&self, name: &str) -> f32 {
+        self.param(name).unwrap_or(0.0)
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.param(name).unwrap_or(0.0) != 0.0
+    }
+}
+
+/// A behavior with the parameters of well-known templates as named fields
+///
+/// Parameters that are missing from `BehaviorParameter` are `0`, `false` or `None`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "template", rename_all = "camelCase")]
+pub enum Behavior {
+    /// `BasicAttack` (1): deal damage to the target
+    #[serde(rename_all = "camelCase")]
+    BasicAttack {
+        /// `min damage`
+        min_damage: i32,
+        /// `max damage`
+        max_damage: i32,
+        /// `on_success`
+        on_success: Option<BehaviorId>,
+        /// `on_fail_armor`
+        on_fail_armor: Option<BehaviorId>,
+        /// `on_fail_immune`
+        on_fail_immune: Option<BehaviorId>,
+        /// `on_fail_blocked`
+        on_fail_blocked: Option<BehaviorId>,
+    },
+    /// `TacArc` (2): pick targets in an arc in front of the caster
+    #[serde(rename_all = "camelCase")]
+    TacArc {
+        /// `action`
+        action: Option<BehaviorId>,
+        /// `blocked action`
+        blocked_action: Option<BehaviorId>,
+        /// `miss action`
+        miss_action: Option<BehaviorId>,
+        /// `max targets`
+        max_targets: i32,
+        /// `min range`
+        min_range: f32,
+        /// `max range`
+        max_range: f32,
+        /// `angle`
+        angle: f32,
+        /// `use_picked_target`
+        use_picked_target: bool,
+    },
+    /// `And` (3): run all behaviors
+    And {
+        /// `behavior 1` to `behavior N`
+        behaviors: Vec<BehaviorId>,
+    },
+    /// `ProjectileAttack` (4): fire projectiles
+    #[serde(rename_all = "camelCase")]
+    ProjectileAttack {
+        /// `LOT_ID`
+        lot: i32,
+        /// `projectile_speed`
+        projectile_speed: f32,
+        /// `max_distance`
+        max_distance: f32,
+        /// `spread_count`
+        spread_count: i32,
+        /// `spread_angle`
+        spread_angle: f32,
+        /// `track_target`
+        track_target: bool,
+    },
+    /// `Heal` (5): restore health
+    Heal {
+        /// `health`
+        health: i32,
+    },
+    /// `MovementSwitch` (6): pick a behavior depending on the movement state
+    #[serde(rename_all = "camelCase")]
+    MovementSwitch {
+        /// `moving_action`
+        moving_action: Option<BehaviorId>,
+        /// `ground_action`
+        ground_action: Option<BehaviorId>,
+        /// `jump_action`
+        jump_action: Option<BehaviorId>,
+        /// `falling_action`
+        falling_action: Option<BehaviorId>,
+        /// `double_jump_action`
+        double_jump_action: Option<BehaviorId>,
+        /// `air_action`
+        air_action: Option<BehaviorId>,
+        /// `jetpack_action`
+        jetpack_action: Option<BehaviorId>,
+    },
+    /// `AreaOfEffect` (7): run a behavior for all targets in a radius
+    #[serde(rename_all = "camelCase")]
+    AreaOfEffect {
+        /// `action`
+        action: Option<BehaviorId>,
+        /// `radius`
+        radius: f32,
+        /// `max targets`
+        max_targets: i32,
+        /// `ignore_faction`
+        ignore_faction: bool,
+        /// `include_faction`
+        include_faction: bool,
+        /// `target_self`
+        target_self: bool,
+    },
+    /// `OverTime` (12): run a behavior in intervals
+    #[serde(rename_all = "camelCase")]
+    OverTime {
+        /// `action`
+        action: Option<BehaviorId>,
+        /// `num_intervals`
+        num_intervals: i32,
+        /// `delay`
+        delay: f32,
+    },
+    /// `Imagination` (13): restore imagination
+    Imagination {
+        /// `imagination`
+        imagination: i32,
+    },
+    /// `TargetCaster` (14): run a behavior on the caster
+    TargetCaster {
+        /// `action`
+        action: Option<BehaviorId>,
+    },
+    /// `Duration` (16): run a behavior for some time
+    Duration {
+        /// `action`
+        action: Option<BehaviorId>,
+        /// `duration`
+        duration: f32,
+    },
+    /// `Knockback` (17): push the target away
+    Knockback {
+        /// `strength`
+        strength: f32,
+        /// `angle`
+        angle: f32,
+    },
+    /// `AttackDelay` (18): run a behavior after a delay
+    #[serde(rename_all = "camelCase")]
+    AttackDelay {
+        /// `action`
+        action: Option<BehaviorId>,
+        /// `delay`
+        delay: f32,
+        /// `num_intervals`
+        num_intervals: i32,
+    },
+    /// `SpawnObject` (27): spawn an object
+    SpawnObject {
+        /// `LOT_ID`
+        lot: i32,
+        /// `distance`
+        distance: f32,
+    },
+    /// `Switch` (29): pick one of two behaviors
+    #[serde(rename_all = "camelCase")]
+    Switch {
+        /// `action_true`
+        action_true: Option<BehaviorId>,
+        /// `action_false`
+        action_false: Option<BehaviorId>,
+        /// `imagination`
+        imagination: i32,
+    },
+    /// `Buff` (30): increase the maximum stats
+    #[serde(rename_all = "camelCase")]
+    Buff {
+        /// `life`
+        life: i32,
+        /// `armor`
+        armor: i32,
+        /// `imag`
+        imagination: i32,
+        /// `run_speed`
+        run_speed: f32,
+        /// `walk_speed`
+        walk_speed: f32,
+    },
+    /// `ApplyBuff` (37): apply an entry from `BuffDefinitions`
+    #[serde(rename_all = "camelCase")]
+    ApplyBuff {
+        /// `buff_id`
+        buff_id: i32,
+        /// `duration_secs`
+        duration_secs: f32,
+    },
+    /// `Chain` (38): run the behavior picked by the chain index
+    #[serde(rename_all = "camelCase")]
+    Chain {
+        /// `behavior 1` to `behavior N`
+        behaviors: Vec<BehaviorId>,
+        /// `chain_delay`
+        chain_delay: f32,
+    },
+    /// `ChargeUp` (43): run a behavior after charging up
+    #[serde(rename_all = "camelCase")]
+    ChargeUp {
+        /// `action`
+        action: Option<BehaviorId>,
+        /// `max_duration`
+        max_duration: f32,
+    },
+    /// `SwitchMultiple` (44): pick a behavior depending on a value
+    SwitchMultiple {
+        /// `value N` and `behavior N`, for N from 1
+        behaviors: Vec<(f32, BehaviorId)>,
+    },
+    /// `Start` (45): start a behavior that is ended by `End`
+    Start {
+        /// `action`
+        action: Option<BehaviorId>,
+    },
+    /// `End` (46): end a behavior that was started by `Start`
+    #[serde(rename_all = "camelCase")]
+    End {
+        /// `start_action`
+        start_action: Option<BehaviorId>,
+    },
+    /// Any other template
+    #[serde(rename_all = "camelCase")]
+    Other {
+        /// The ID of the template
+        template_id: i32,
+    },
+}
+
+impl Behavior {
+    /// Convert a generic behavior node
+    pub fn from_node(node: &BehaviorNode) -> Self {
+        match node.template_id {
+            1 => Self::BasicAttack {
+                min_damage: node.int("min damage"),
+                max_damage: node.int("max damage"),
+                on_success: node.action("on_success"),
+                on_fail_armor: node.action("on_fail_armor"),
+                on_fail_immune: node.action("on_fail_immune"),
+                on_fail_blocked: node.action("on_fail_blocked"),
+            },
+            2 => Self::TacArc {
+                action: node.action("action"),
+                blocked_action: node.action("blocked action"),
+                miss_action: node.action("miss action"),
+                max_targets: node.int("max targets"),
+                min_range: node.float("min range"),
+                max_range: node.float("max range"),
+                angle: node.float("angle"),
+                use_picked_target: node.flag("use_picked_target"),
+            },
+            3 => Self::And {
+                behaviors: node.numbered_actions("behavior"),
+            },
+            4 => Self::ProjectileAttack {
+                lot: node.int("LOT_ID"),
+                projectile_speed: node.float("projectile_speed"),
+                max_distance: node.float("max_distance"),
+                spread_count: node.int("spread_count"),
+                spread_angle: node.float("spread_angle"),
+                track_target: node.flag("track_target"),
+            },
+            5 => Self::Heal {
+                health: node.int("health"),
+            },
+            6 => Self::MovementSwitch {
+                moving_action: node.action("moving_action"),
+                ground_action: node.action("ground_action"),
+                jump_action: node.action("jump_action"),
+                falling_action: node.action("falling_action"),
+                double_jump_action: node.action("double_jump_action"),
+                air_action: node.action("air_action"),
+                jetpack_action: node.action("jetpack_action"),
+            },
+            7 => Self::AreaOfEffect {
+                action: node.action("action"),
+                radius: node.float("radius"),
+                max_targets: node.int("max targets"),
+                ignore_faction: node.flag("ignore_faction"),
+                include_faction: node.flag("include_faction"),
+                target_self: node.flag("target_self"),
+            },
+            12 => Self::OverTime {
+                action: node.action("action"),
+                num_intervals: node.int("num_intervals"),
+                delay: node.float("delay"),
+            },
+            13 => Self::Imagination {
+                imagination: node.int("imagination"),
+            },
+            14 => Self::TargetCaster {
+                action: node.action("action"),
+            },
+            16 => Self::Duration {
+                action: node.action("action"),
+                duration: node.float("duration"),
+            },
+            17 => Self::Knockback {
+                strength: node.float("strength"),
+                angle: node.float("angle"),
+            },
+            18 => Self::AttackDelay {
+                action: node.action("action"),
+                delay: node.float("delay"),
+                num_intervals: node.int("num_intervals"),
+            },
+            27 => Self::SpawnObject {
+                lot: node.int("LOT_ID"),
+                distance: node.float("distance"),
+            },
+            29 => Self::Switch {
+                action_true: node.action("action_true"),
+                action_false: node.action("action_false"),
+                imagination: node.int("imagination"),
+            },
+            30 => Self::Buff {
+                life: node.int("life"),
+                armor: node.int("armor"),
+                imagination: node.int("imag"),
+                run_speed: node.float("run_speed"),
+                walk_speed: node.float("walk_speed"),
+            },
+            37 => Self::ApplyBuff {
+                buff_id: node.int("buff_id"),
+                duration_secs: node.float("duration_secs"),
+            },
+            38 => Self::Chain {
+                behaviors: node.numbered_actions("behavior"),
+                chain_delay: node.float("chain_delay"),
+            },
+            43 => Self::ChargeUp {
+                action: node.action("action"),
+                max_duration: node.float("max_duration"),
+            },
+            44 => Self::SwitchMultiple {
+                behaviors: (1..)
+                    .map(|i| {
+                        let value = node.param(&format!("value {}", i))?;
+                        let action = node.action(&format!("behavior {}", i))?;
+                        Some((value, action))
+                    })
+                    .take_while(Option::is_some)
+                    .flatten()
+                    .collect(),
+            },
+            45 => Self::Start {
+                action: node.action("action"),
+            },
+            46 => Self::End {
+                start_action: node.action("start_action"),
+            },
+            template_id => Self::Other { template_id },
+        }
+    }
+}
+
+impl<'db> TypedDatabase<'db> {
+    /// Load a single behavior with typed parameters
+    pub fn get_behavior(&self, id: i32) -> Option<Behavior> {
+        self.get_behavior_node(id)
+            .map(|node| Behavior::from_node(&node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{
+        is_behavior_param, Behavior, BehaviorBackEdge, BehaviorGraph, BehaviorId, BehaviorNode,
+    };
+    use crate::ext::test_util::ids;
+
+    #[test]
+    fn test_is_behavior_param() {
+        for name in &[
+            "action",
+            "blocked action",
+            "behavior 1",
+            "on_success",
+            "miss",
+            "blocked",
+        ] {
+            assert!(is_behavior_param(name), "{}", name);
+        }
+        for name in &[
+            "min damage",
+            "max targets",
+            "angle",
+            "missed",
+            "chain_behavior",
+        ] {
+            assert!(!is_behavior_param(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_graph_back_edges_and_missing() {
+        // 1 -> 2 -> 3 -> 1, 2 -> 4 (missing), 1 -> 3
+        let links: BTreeMap<i32, Vec<(&str, i32)>> = vec![
+            (1, vec![("action", 2), ("on_success", 3)]),
+            (2, vec![("behavior 1", 3), ("behavior 2", 4)]),
+            (3, vec![("action", 1)]),
+        ]
+        .into_iter()
+        .collect();
+        let graph = BehaviorGraph::build(1, |id| {
+            links.get(&id).map(|children| BehaviorNode {
+                id,
+                children: children.iter().map(|&(k, v)| (k.to_owned(), v)).collect(),
+                ..Default::default()
+            })
+        });
+        assert_eq!(
+            graph.nodes.keys().copied().collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(graph.missing, ids(&[4]));
+        assert!(graph.has_cycle());
+        assert_eq!(
+            graph.back_edges,
+            vec![BehaviorBackEdge {
+                from: 3,
+                parameter: "action".to_owned(),
+                to: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_from_node_child_reference() {
+        let node = BehaviorNode {
+            template_id: 38,
+            parameters: vec![
+                ("behavior 1".to_owned(), 10.0),
+                ("behavior 2".to_owned(), 11.0),
+                ("behavior 4".to_owned(), 13.0),
+                ("chain_delay".to_owned(), 0.5),
+            ]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+        // The numbered list stops at the first gap
+        assert_eq!(
+            Behavior::from_node(&node),
+            Behavior::Chain {
+                behaviors: vec![BehaviorId(10), BehaviorId(11)],
+                chain_delay: 0.5,
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_node_optional_parameter() {
+        let node = BehaviorNode {
+            template_id: 1,
+            parameters: vec![
+                ("min damage".to_owned(), 2.0),
+                ("max damage".to_owned(), 3.0),
+                ("on_success".to_owned(), 20.0),
+                ("on_fail_armor".to_owned(), 0.0),
+            ]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+        assert_eq!(
+            Behavior::from_node(&node),
+            Behavior::BasicAttack {
+                min_damage: 2,
+                max_damage: 3,
+                on_success: Some(BehaviorId(20)),
+                on_fail_armor: None,
+                on_fail_immune: None,
+                on_fail_blocked: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_node_missing_parameters() {
+        let node = BehaviorNode {
+            template_id: 2,
+            ..Default::default()
+        };
+        assert_eq!(
+            Behavior::from_node(&node),
+            Behavior::TacArc {
+                action: None,
+                blocked_action: None,
+                miss_action: None,
+                max_targets: 0,
+                min_range: 0.0,
+                max_range: 0.0,
+                angle: 0.0,
+                use_picked_target: false,
+            }
+        );
+        let node = BehaviorNode {
+            template_id: 9999,
+            ..Default::default()
+        };
+        assert_eq!(
+            Behavior::from_node(&node),
+            Behavior::Other { template_id: 9999 }
+        );
+    }
+}
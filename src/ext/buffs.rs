@@ -0,0 +1,159 @@
+//! # Buffs
+//!
+//! `BuffDefinitions` has one row per buff, and `BuffParameters` stores its
+//! named parameters, each with a number, string or effect value. An `EffectID`
+//! refers to the rows of `BehaviorEffect` with that `effectID`.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{rows::BehaviorEffectRow, TypedDatabase};
+
+/// The value of a buff parameter
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuffParameter {
+    /// `NumberValue`
+    pub number: Option<f32>,
+    /// `StringValue`
+    pub string: Option<String>,
+    /// `EffectID`
+    pub effect_id: Option<i32>,
+}
+
+impl BuffParameter {
+    /// Get the `StringValue` as a comma separated list of numbers
+    ///
+    /// Invalid entries are skipped.
+    pub fn number_list(&self) -> Vec<f32> {
+        self.string
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|s| s.trim().parse().ok())
+            .collect()
+    }
+}
+
+/// A buff with its parameters
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Buff {
+    /// The ID of the buff
+    pub id: i32,
+    /// The priority of the buff
+    pub priority: f32,
+    /// The icon shown in the UI
+    pub ui_icon: Option<String>,
+    /// The parameters, by name
+    pub parameters: BTreeMap<String, BuffParameter>,
+}
+
+impl Buff {
+    /// Get a parameter by name
+    pub fn param(&self, name: &str) -> Option<&BuffParameter> {
+        self.parameters.get(name)
+    }
+
+    /// Get the `NumberValue` of a parameter
+    pub fn number(&self, name: &str) -> Option<f32> {
+        self.param(name)?.number
+    }
+
+    /// Get the `StringValue` of a parameter
+    pub fn string(&self, name: &str) -> Option<&str> {
+        self.param(name)?.string.as_deref()
+    }
+
+    /// Get the `EffectID` of a parameter
+    pub fn effect_id(&self, name: &str) -> Option<i32> {
+        self.param(name)?.effect_id
+    }
+
+    /// Get the increase of the maximum health (`max_health`)
+    pub fn max_health(&self) -> Option<f32> {
+        self.number("max_health")
+    }
+
+    /// Get the increase of the maximum armor (`max_armor`)
+    pub fn max_armor(&self) -> Option<f32> {
+        self.number("max_armor")
+    }
+
+    /// Get the increase of the maximum imagination (`max_imagination`)
+    pub fn max_imagination(&self) -> Option<f32> {
+        self.number("max_imagination")
+    }
+
+    /// Get the change of the movement speed (`speed`)
+    pub fn speed(&self) -> Option<f32> {
+        self.number("speed")
+    }
+
+    /// Iterate over the parameters that have an `EffectID`
+    pub fn effect_ids(&self) -> impl Iterator<Item = (&str, i32)> {
+        self.parameters
+            .iter()
+            .filter_map(|(name, param)| Some((name.as_str(), param.effect_id?)))
+    }
+}
+
+impl<'db> TypedDatabase<'db> {
+    /// Get the rows of `BehaviorEffect` for an effect ID, one per `effectType`
+    pub fn get_behavior_effects(&self, effect_id: i32) -> Vec<BehaviorEffectRow<'_, '_>> {
+        match &self.behavior_effect {
+            Some(table) => table.key_iter(effect_id).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Get the `BehaviorEffect` rows of all parameters of a buff, by parameter name
+    pub fn get_buff_effects<'b>(
+        &self,
+        buff: &'b Buff,
+    ) -> Vec<(&'b str, BehaviorEffectRow<'_, '_>)> {
+        buff.effect_ids()
+            .flat_map(|(name, effect_id)| {
+                let rows = self.get_behavior_effects(effect_id);
+                if rows.is_empty() {
+                    log::warn!("Missing BehaviorEffect {} for buff {}", effect_id, buff.id);
+                }
+                rows.into_iter().map(move |row| (name, row))
+            })
+            .collect()
+    }
+
+    /// Get all buff IDs from `BuffDefinitions`
+    pub fn buff_ids(&self) -> Vec<i32> {
+        match &self.buff_definitions {
+            Some(table) => table.row_iter().map(|row| row.id()).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Get a buff with its parameters
+    pub fn get_buff(&self, id: i32) -> Option<Buff> {
+        let row = self.buff_definitions.as_ref()?.key_iter(id).next()?;
+        let parameters = match &self.buff_parameters {
+            Some(table) => table
+                .key_iter(id)
+                .map(|row| {
+                    let param = BuffParameter {
+                        number: row.number_value(),
+                        string: row.string_value().map(|s| s.decode().into_owned()),
+                        effect_id: row.effect_id(),
+                    };
+                    (row.parameter_name().decode().into_owned(), param)
+                })
+                .collect(),
+            None => BTreeMap::new(),
+        };
+        Some(Buff {
+            id,
+            priority: row.priority(),
+            ui_icon: row.ui_icon().map(|s| s.decode().into_owned()),
+            parameters,
+        })
+    }
+}
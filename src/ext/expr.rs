@@ -0,0 +1,168 @@
+//! # Boolean ID expressions
+//!
+//! Both `Missions.prereqMissionID` and the precondition columns use the same
+//! small expression language over numeric IDs:
+//!
+//! - `|` means *any of*
+//! - the AND separators (`,` and `&`, plus `;` for preconditions) mean *all of*
+//!   and bind tighter than `|`
+//! - parentheses group sub-expressions
+//! - whitespace is ignored
+//!
+//! The atoms start with a number and may have a suffix, e.g. `123:4` for missions.
+
+use std::{error::Error, fmt, iter::Peekable, str::CharIndices};
+
+/// Error when parsing an ID expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExprParseError {
+    /// An unexpected character at the given byte offset
+    UnexpectedChar(char, usize),
+    /// The expression ended where an ID or `(` was expected
+    UnexpectedEnd,
+    /// A number that does not fit into an `i32`, at the given byte offset
+    InvalidNumber(usize),
+}
+
+impl fmt::Display for ExprParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedChar(c, pos) => write!(f, "unexpected {:?} at offset {}", c, pos),
+            Self::UnexpectedEnd => write!(f, "unexpected end of expression"),
+            Self::InvalidNumber(pos) => write!(f, "invalid number at offset {}", pos),
+        }
+    }
+}
+
+impl Error for ExprParseError {}
+
+/// An expression type that can be parsed with [`parse`]
+pub(crate) trait Grammar: Sized {
+    /// The characters that mean *all of*
+    const AND: &'static [char];
+
+    /// Build an expression where all of the sub-expressions need to be satisfied
+    fn all(list: Vec<Self>) -> Self;
+
+    /// Build an expression where any of the sub-expressions needs to be satisfied
+    fn any(list: Vec<Self>) -> Self;
+
+    /// Parse the rest of an atom that starts with the number `id`
+    fn atom(id: i32, parser: &mut Parser<'_>) -> Result<Self, ExprParseError>;
+}
+
+/// The state of the parser, used by [`Grammar::atom`]
+pub(crate) struct Parser<'s> {
+    src: &'s str,
+    chars: Peekable<CharIndices<'s>>,
+}
+
+impl<'s> Parser<'s> {
+    /// Get the next character that is not whitespace, without consuming it
+    pub fn peek(&mut self) -> Option<(usize, char)> {
+        while let Some(&(_, c)) = self.chars.peek() {
+            if c.is_whitespace() {
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+        self.chars.peek().copied()
+    }
+
+    /// Consume the character returned by [`Parser::peek`]
+    pub fn bump(&mut self) {
+        self.chars.next();
+    }
+
+    /// Parse a number, which must start at the next character
+    pub fn number(&mut self) -> Result<i32, ExprParseError> {
+        let start = match self.peek() {
+            Some((pos, c)) if c.is_ascii_digit() => pos,
+            Some((pos, c)) => return Err(ExprParseError::UnexpectedChar(c, pos)),
+            None => return Err(ExprParseError::UnexpectedEnd),
+        };
+        let mut end = start;
+        while let Some(&(pos, c)) = self.chars.peek() {
+            if c.is_ascii_digit() {
+                end = pos + 1;
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+        self.src[start..end]
+            .parse()
+            .map_err(|_| ExprParseError::InvalidNumber(start))
+    }
+
+    fn parse_any<E: Grammar>(&mut self) -> Result<E, ExprParseError> {
+        let mut list = vec![self.parse_all()?];
+        while let Some((_, '|')) = self.peek() {
+            self.bump();
+            list.push(self.parse_all()?);
+        }
+        Ok(if list.len() == 1 {
+            list.pop().unwrap()
+        } else {
+            E::any(list)
+        })
+    }
+
+    fn parse_all<E: Grammar>(&mut self) -> Result<E, ExprParseError> {
+        let mut list = vec![self.parse_atom()?];
+        while let Some((_, c)) = self.peek() {
+            if !E::AND.contains(&c) {
+                break;
+            }
+            self.bump();
+            list.push(self.parse_atom()?);
+        }
+        Ok(if list.len() == 1 {
+            list.pop().unwrap()
+        } else {
+            E::all(list)
+        })
+    }
+
+    fn parse_atom<E: Grammar>(&mut self) -> Result<E, ExprParseError> {
+        match self.peek() {
+            Some((_, '(')) => {
+                self.bump();
+                let inner = self.parse_any()?;
+                match self.peek() {
+                    Some((_, ')')) => {
+                        self.bump();
+                        Ok(inner)
+                    }
+                    Some((pos, c)) => Err(ExprParseError::UnexpectedChar(c, pos)),
+                    None => Err(ExprParseError::UnexpectedEnd),
+                }
+            }
+            Some((_, c)) if c.is_ascii_digit() => {
+                let id = self.number()?;
+                E::atom(id, self)
+            }
+            Some((pos, c)) => Err(ExprParseError::UnexpectedChar(c, pos)),
+            None => Err(ExprParseError::UnexpectedEnd),
+        }
+    }
+}
+
+/// Parse an expression
+///
+/// An empty string results in an empty [`Grammar::all`].
+pub(crate) fn parse<E: Grammar>(src: &str) -> Result<E, ExprParseError> {
+    let mut parser = Parser {
+        src,
+        chars: src.char_indices().peekable(),
+    };
+    if parser.peek().is_none() {
+        return Ok(E::all(Vec::new()));
+    }
+    let expr = parser.parse_any()?;
+    match parser.peek() {
+        Some((pos, c)) => Err(ExprParseError::UnexpectedChar(c, pos)),
+        None => Ok(expr),
+    }
+}
@@ -0,0 +1,174 @@
+//! # Factions
+//!
+//! Every row in `Factions` lists the factions it treats as friends (`friendList`)
+//! and as enemies (`enemyList`). Objects belong to factions through their
+//! `DestructibleComponent`, which has a primary `faction` and an additional
+//! `factionList`.
+//!
+//! `Factions` also has a `factionList` and a `factionListFriendly` flag. It is not
+//! known how the client uses them, so they are only exposed on
+//! [`FactionRelations`] and not taken into account by [`FactionGraph::is_enemy`]
+//! and [`FactionGraph::is_friend`].
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use super::parse_id_list;
+use crate::TypedDatabase;
+
+/// The relations of a single faction
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FactionRelations {
+    /// The factions this faction is friendly towards
+    pub friends: BTreeSet<i32>,
+    /// The factions this faction is hostile towards
+    pub enemies: BTreeSet<i32>,
+    /// `factionList`
+    pub faction_list: BTreeSet<i32>,
+    /// `factionListFriendly`
+    pub faction_list_friendly: bool,
+}
+
+/// The parsed `Factions` table
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FactionGraph {
+    factions: BTreeMap<i32, FactionRelations>,
+}
+
+impl FactionGraph {
+    /// Load the graph from the database
+    pub fn new(db: &TypedDatabase) -> Self {
+        let mut factions: BTreeMap<i32, FactionRelations> = BTreeMap::new();
+        if let Some(table) = &db.factions {
+            for row in table.row_iter() {
+                let entry = factions.entry(row.faction()).or_default();
+                if let Some(list) = row.friend_list() {
+                    entry.friends.extend(parse_id_list(list));
+                }
+                if let Some(list) = row.enemy_list() {
+                    entry.enemies.extend(parse_id_list(list));
+                }
+                entry.faction_list.extend(parse_id_list(row.faction_list()));
+                entry.faction_list_friendly |= row.faction_list_friendly();
+            }
+        }
+        Self { factions }
+    }
+
+    /// Get the relations of a faction
+    pub fn get(&self, faction: i32) -> Option<&FactionRelations> {
+        self.factions.get(&faction)
+    }
+
+    /// Iterate over all factions
+    pub fn iter(&self) -> impl Iterator<Item = (i32, &FactionRelations)> {
+        self.factions.iter().map(|(&id, rel)| (id, rel))
+    }
+
+    /// Whether faction `a` is hostile towards faction `b`
+    pub fn is_enemy(&self, a: i32, b: i32) -> bool {
+        self.get(a).map_or(false, |rel| rel.enemies.contains(&b))
+    }
+
+    /// Whether faction `a` is friendly towards faction `b`
+    pub fn is_friend(&self, a: i32, b: i32) -> bool {
+        self.get(a).map_or(false, |rel| rel.friends.contains(&b))
+    }
+
+    /// Whether any faction in `a` is hostile towards any faction in `b`
+    pub fn any_enemy(&self, a: &[i32], b: &[i32]) -> bool {
+        a.iter()
+            .any(|&fa| b.iter().any(|&fb| self.is_enemy(fa, fb)))
+    }
+
+    /// Whether any faction in `a` is friendly towards any faction in `b`
+    pub fn any_friend(&self, a: &[i32], b: &[i32]) -> bool {
+        a.iter()
+            .any(|&fa| b.iter().any(|&fb| self.is_friend(fa, fb)))
+    }
+}
+
+impl<'db> TypedDatabase<'db> {
+    /// Load the [`FactionGraph`]
+    pub fn faction_graph(&self) -> FactionGraph {
+        FactionGraph::new(self)
+    }
+
+    /// Get the factions of an object from its `DestructibleComponent`
+    ///
+    /// The primary `faction` comes first, followed by the entries of
+    /// `factionList` that are not already present.
+    pub fn get_object_factions(&self, lot: i32) -> Vec<i32> {
+        let id = match self.get_components(lot).destructible {
+            Some(id) => id,
+            None => return Vec::new(),
+        };
+        let row = match self.destructible_component.key_iter(id).next() {
+            Some(row) => row,
+            None => return Vec::new(),
+        };
+        let mut factions: Vec<i32> = row.faction().into_iter().collect();
+        for faction in parse_id_list(row.faction_list()) {
+            if !factions.contains(&faction) {
+                factions.push(faction);
+            }
+        }
+        factions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FactionGraph, FactionRelations};
+    use crate::ext::test_util::ids;
+
+    fn graph() -> FactionGraph {
+        let factions = vec![
+            (
+                1,
+                FactionRelations {
+                    friends: ids(&[1, 2]),
+                    enemies: ids(&[3]),
+                    ..Default::default()
+                },
+            ),
+            (
+                3,
+                FactionRelations {
+                    enemies: ids(&[1, 2]),
+                    ..Default::default()
+                },
+            ),
+        ];
+        FactionGraph {
+            factions: factions.into_iter().collect(),
+        }
+    }
+
+    #[test]
+    fn test_is_enemy() {
+        let graph = graph();
+        assert!(graph.is_enemy(1, 3));
+        assert!(graph.is_enemy(3, 2));
+        assert!(!graph.is_enemy(1, 2));
+        // Relations are one-sided
+        assert!(!graph.is_enemy(2, 3));
+        assert!(!graph.is_enemy(99, 1));
+        assert!(graph.any_enemy(&[2, 3], &[1]));
+        assert!(!graph.any_enemy(&[2], &[1, 3]));
+    }
+
+    #[test]
+    fn test_is_friend() {
+        let graph = graph();
+        assert!(graph.is_friend(1, 1));
+        assert!(graph.is_friend(1, 2));
+        assert!(!graph.is_friend(2, 1));
+        assert!(!graph.is_friend(3, 3));
+        assert!(!graph.is_friend(99, 99));
+        assert!(graph.any_friend(&[3, 1], &[2]));
+        assert!(!graph.any_friend(&[], &[1]));
+    }
+}
@@ -0,0 +1,445 @@
+//! # Items
+//!
+//! Helpers for the `ItemComponent`, `InventoryComponent` and `mapItemTypes` tables.
+
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use latin1str::Latin1Str;
+use serde::Serialize;
+
+use crate::{rows::ItemComponentRow, TypedDatabase};
+
+/// Parse a list of LOT and amount pairs
+///
+/// Both `lot:count,lot:count` (or with `;` between the pairs) and
+/// `lot,count;lot,count` are accepted. Invalid entries are skipped.
+pub fn parse_lot_counts(text: &Latin1Str) -> Vec<(i32, i32)> {
+    let text = text.decode();
+    let (list_sep, pair_sep): (&[char], char) = if text.contains(':') {
+        (&[',', ';'], ':')
+    } else {
+        (&[';'], ',')
+    };
+    text.split(list_sep)
+        .filter_map(|entry| {
+            let (lot, count) = entry.split_once(pair_sep)?;
+            let lot = lot.trim().parse().ok()?;
+            let count = count.trim().parse().ok()?;
+            Some((lot, count))
+        })
+        .collect()
+}
+
+impl<'a, 'b> ItemComponentRow<'a, 'b> {
+    /// Get the parsed `currencyCosts` as `(lot, count)` pairs
+    pub fn currency_cost_list(&self) -> Vec<(i32, i32)> {
+        self.currency_costs()
+            .map(parse_lot_counts)
+            .unwrap_or_default()
+    }
+
+    /// Get the parsed `ingredientInfo` as `(lot, count)` pairs
+    pub fn ingredient_list(&self) -> Vec<(i32, i32)> {
+        self.ingredient_info()
+            .map(parse_lot_counts)
+            .unwrap_or_default()
+    }
+}
+
+/// An item in the starting inventory of an object
+#[derive(Debug, Copy, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InventoryItem<'a> {
+    /// The LOT of the item
+    pub lot: i32,
+    /// The number of items
+    pub count: i32,
+    /// Whether the item is equipped
+    pub equip: bool,
+    /// The `equipLocation` of the item
+    pub equip_location: Option<&'a Latin1Str>,
+}
+
+/// A location where an item can be equipped
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EquipSlot {
+    /// `hair` (hair and hats)
+    Hair,
+    /// `clavicle` (neck and back)
+    Clavicle,
+    /// `chest`
+    Chest,
+    /// `legs`
+    Legs,
+    /// `special_l` (left hand)
+    LeftHand,
+    /// `special_r` (right hand)
+    RightHand,
+    /// Any other location
+    Other(String),
+}
+
+impl EquipSlot {
+    /// Whether this is one of the hand slots
+    pub fn is_hand(&self) -> bool {
+        matches!(self, Self::LeftHand | Self::RightHand)
+    }
+
+    /// Get the name used in the database
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Hair => "hair",
+            Self::Clavicle => "clavicle",
+            Self::Chest => "chest",
+            Self::Legs => "legs",
+            Self::LeftHand => "special_l",
+            Self::RightHand => "special_r",
+            Self::Other(name) => name,
+        }
+    }
+
+    /// Parse a comma separated list of equip locations
+    pub fn parse_list(text: &Latin1Str) -> Vec<Self> {
+        text.decode()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse().unwrap())
+            .collect()
+    }
+}
+
+impl FromStr for EquipSlot {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "hair" => Self::Hair,
+            "clavicle" => Self::Clavicle,
+            "chest" => Self::Chest,
+            "legs" => Self::Legs,
+            "special_l" => Self::LeftHand,
+            "special_r" => Self::RightHand,
+            _ => Self::Other(s.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for EquipSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for EquipSlot {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// An entry in the `mapItemTypes` table
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemType {
+    /// The ID of the item type (`ItemComponent.itemType`)
+    pub id: i32,
+    /// The description of the item type
+    pub description: String,
+    /// The default equip locations of items of this type
+    pub equip_slots: Vec<EquipSlot>,
+}
+
+/// The data needed to validate equipping an item
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EquipInfo {
+    /// The LOT of the item
+    pub lot: i32,
+    /// The item type (`ItemComponent.itemType`)
+    pub item_type: i32,
+    /// The slots the item occupies
+    pub equip_slots: Vec<EquipSlot>,
+    /// Whether the item needs both hands
+    pub is_two_handed: bool,
+}
+
+impl EquipInfo {
+    /// Whether the item can be equipped at all
+    pub fn is_equippable(&self) -> bool {
+        !self.equip_slots.is_empty()
+    }
+
+    fn uses_hands(&self) -> bool {
+        self.is_two_handed || self.equip_slots.iter().any(EquipSlot::is_hand)
+    }
+
+    /// Whether this item and `other` can't be equipped at the same time
+    ///
+    /// Items conflict if they share a slot, or if one of them is two-handed and
+    /// the other one uses a hand slot.
+    pub fn conflicts_with(&self, other: &EquipInfo) -> bool {
+        let shared = self
+            .equip_slots
+            .iter()
+            .any(|slot| other.equip_slots.contains(slot));
+        let hands = (self.is_two_handed && other.uses_hands())
+            || (other.is_two_handed && self.uses_hands());
+        shared || hands
+    }
+}
+
+/// Parse an `equipLocation`, falling back to the slots of the item type
+fn equip_slots_or_default<F>(location: Option<&Latin1Str>, item_type: F) -> Vec<EquipSlot>
+where
+    F: FnOnce() -> Option<ItemType>,
+{
+    let slots = location.map(EquipSlot::parse_list).unwrap_or_default();
+    if slots.is_empty() {
+        item_type().map(|ty| ty.equip_slots).unwrap_or_default()
+    } else {
+        slots
+    }
+}
+
+impl<'db> TypedDatabase<'db> {
+    /// Get the items an object spawns with, from `InventoryComponent`
+    pub fn get_starting_inventory(&self, lot: i32) -> Vec<InventoryItem<'_>> {
+        let id = match self.get_components(lot).inventory {
+            Some(id) => id,
+            None => return Vec::new(),
+        };
+        self.inventory_component
+            .key_iter(id)
+            .map(|row| InventoryItem {
+                lot: row.itemid(),
+                count: row.count(),
+                equip: row.equip(),
+                equip_location: self
+                    .get_item_component(row.itemid())
+                    .and_then(|comp| comp.equip_location())
+                    .filter(|loc| !loc.is_empty()),
+            })
+            .collect()
+    }
+
+    /// Get the equipped items of an object by equip slot
+    ///
+    /// If there are multiple items for one slot, the last one wins.
+    pub fn get_visual_loadout(&self, lot: i32) -> BTreeMap<EquipSlot, i32> {
+        let mut loadout = BTreeMap::new();
+        for item in self.get_starting_inventory(lot) {
+            if let (true, Some(location)) = (item.equip, item.equip_location) {
+                for slot in EquipSlot::parse_list(location) {
+                    loadout.insert(slot, item.lot);
+                }
+            }
+        }
+        loadout
+    }
+
+    /// Get an entry from `mapItemTypes`
+    pub fn get_item_type(&self, id: i32) -> Option<ItemType> {
+        let row = self.map_item_types.as_ref()?.key_iter(id).next()?;
+        Some(ItemType {
+            id,
+            description: row.description().decode().into_owned(),
+            equip_slots: row
+                .equip_location()
+                .map(EquipSlot::parse_list)
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Get all entries from `mapItemTypes`
+    pub fn get_item_types(&self) -> BTreeMap<i32, ItemType> {
+        let table = match &self.map_item_types {
+            Some(table) => table,
+            None => return BTreeMap::new(),
+        };
+        table
+            .row_iter()
+            .map(|row| {
+                let item_type = ItemType {
+                    id: row.id(),
+                    description: row.description().decode().into_owned(),
+                    equip_slots: row
+                        .equip_location()
+                        .map(EquipSlot::parse_list)
+                        .unwrap_or_default(),
+                };
+                (item_type.id, item_type)
+            })
+            .collect()
+    }
+
+    /// Get the equip data of an item
+    ///
+    /// If the item has no `equipLocation`, the default of its item type is used.
+    pub fn get_equip_info(&self, lot: i32) -> Option<EquipInfo> {
+        let comp = self.get_item_component(lot)?;
+        let item_type = comp.item_type();
+        Some(EquipInfo {
+            lot,
+            item_type,
+            equip_slots: equip_slots_or_default(comp.equip_location(), || {
+                self.get_item_type(item_type)
+            }),
+            is_two_handed: comp.is_two_handed(),
+        })
+    }
+
+    /// Check whether two items can't be equipped at the same time
+    ///
+    /// Returns `None` if one of the items has no item component.
+    pub fn equip_conflict(&self, a: i32, b: i32) -> Option<bool> {
+        let a = self.get_equip_info(a)?;
+        let b = self.get_equip_info(b)?;
+        Some(a.conflicts_with(&b))
+    }
+
+    /// Get the LOTs of all objects that use each item component
+    pub fn item_component_lots(&self) -> BTreeMap<i32, Vec<i32>> {
+        let mut map: BTreeMap<i32, Vec<i32>> = BTreeMap::new();
+        for row in self.comp_reg.row_iter() {
+            if row.component_type() == 11 {
+                map.entry(row.component_id()).or_default().push(row.id());
+            }
+        }
+        map
+    }
+
+    fn items_referencing<F>(&self, f: F) -> Vec<(i32, i32)>
+    where
+        F: Fn(&ItemComponentRow) -> Option<i32>,
+    {
+        let lots = self.item_component_lots();
+        let mut items = Vec::new();
+        for row in self.item_component.row_iter() {
+            if let Some(count) = f(&row) {
+                if let Some(list) = lots.get(&row.id()) {
+                    items.extend(list.iter().map(|&lot| (lot, count)));
+                }
+            }
+        }
+        items
+    }
+
+    /// Get all items that cost the item `lot`, with the amount
+    ///
+    /// This checks `currencyCosts` as well as `currencyLOT` with `altCurrencyCost`.
+    pub fn items_costing(&self, lot: i32) -> Vec<(i32, i32)> {
+        self.items_referencing(|row| {
+            let from_list = row
+                .currency_cost_list()
+                .into_iter()
+                .find(|&(l, _)| l == lot)
+                .map(|(_, count)| count);
+            from_list.or_else(|| {
+                if row.currency_lot() == Some(lot) {
+                    row.alt_currency_cost()
+                } else {
+                    None
+                }
+            })
+        })
+    }
+
+    /// Get all items that are crafted from the item `lot`, with the amount
+    pub fn items_with_ingredient(&self, lot: i32) -> Vec<(i32, i32)> {
+        self.items_referencing(|row| {
+            row.ingredient_list()
+                .into_iter()
+                .find(|&(l, _)| l == lot)
+                .map(|(_, count)| count)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{equip_slots_or_default, parse_lot_counts, EquipInfo, EquipSlot, ItemType};
+    use crate::ext::test_util::latin1;
+
+    fn parse(text: &str) -> Vec<(i32, i32)> {
+        parse_lot_counts(latin1(text))
+    }
+
+    #[test]
+    fn test_lot_colon_count() {
+        assert_eq!(parse("13763:5"), vec![(13763, 5)]);
+        assert_eq!(parse("1:2,3:4"), vec![(1, 2), (3, 4)]);
+        assert_eq!(parse("1:2;3:4"), vec![(1, 2), (3, 4)]);
+    }
+
+    #[test]
+    fn test_lot_comma_count() {
+        assert_eq!(parse("1,2"), vec![(1, 2)]);
+        assert_eq!(parse("1,2;3,4"), vec![(1, 2), (3, 4)]);
+    }
+
+    #[test]
+    fn test_whitespace_and_bad_tokens() {
+        assert_eq!(parse(" 1 : 2 , 3:4 "), vec![(1, 2), (3, 4)]);
+        assert_eq!(parse("1, 2; 3 ,4;"), vec![(1, 2), (3, 4)]);
+        assert_eq!(parse("1:x,y:2,3:4,5"), vec![(3, 4)]);
+        assert_eq!(parse("1,2;3;a,4"), vec![(1, 2)]);
+        assert_eq!(parse(""), vec![]);
+    }
+
+    #[test]
+    fn test_equip_slots_default() {
+        let hat = || {
+            Some(ItemType {
+                id: 1,
+                description: "Hat".to_owned(),
+                equip_slots: vec![EquipSlot::Hair],
+            })
+        };
+        assert_eq!(
+            equip_slots_or_default(Some(latin1("special_r")), hat),
+            vec![EquipSlot::RightHand]
+        );
+        assert_eq!(
+            equip_slots_or_default(Some(latin1("")), hat),
+            vec![EquipSlot::Hair]
+        );
+        assert_eq!(equip_slots_or_default(None, hat), vec![EquipSlot::Hair]);
+        assert_eq!(equip_slots_or_default(None, || None), vec![]);
+    }
+
+    #[test]
+    fn test_conflicts_with() {
+        let sword = EquipInfo {
+            lot: 1,
+            item_type: 4,
+            equip_slots: vec![EquipSlot::RightHand],
+            is_two_handed: false,
+        };
+        let shield = EquipInfo {
+            lot: 2,
+            item_type: 5,
+            equip_slots: vec![EquipSlot::LeftHand],
+            is_two_handed: false,
+        };
+        let staff = EquipInfo {
+            lot: 3,
+            is_two_handed: true,
+            ..sword.clone()
+        };
+        let hat = EquipInfo {
+            lot: 4,
+            item_type: 2,
+            equip_slots: vec![EquipSlot::Hair],
+            is_two_handed: false,
+        };
+        assert!(sword.conflicts_with(&sword.clone()));
+        assert!(!sword.conflicts_with(&shield));
+        assert!(staff.conflicts_with(&shield));
+        assert!(shield.conflicts_with(&staff));
+        assert!(!staff.conflicts_with(&hat));
+        assert!(!hat.conflicts_with(&sword));
+    }
+}
@@ -0,0 +1,386 @@
+//! # Localization
+//!
+//! Rows with the `localize` flag set have their texts in the client's
+//! `locale.xml` instead, with keys like `Objects_<id>_name`. A [`LocaleProvider`]
+//! supplies these texts. With the `quick-xml` feature, `LocaleXml` loads them
+//! from a locale file.
+
+use std::borrow::Cow;
+
+use latin1str::Latin1Str;
+
+use crate::TypedDatabase;
+
+/// The locale used when a text is missing in the requested locale
+pub const DEFAULT_LOCALE: &str = "en_US";
+
+/// A source of localized texts
+pub trait LocaleProvider {
+    /// Get the text for `key` in `locale`
+    fn get(&self, key: &str, locale: &str) -> Option<Cow<'_, str>>;
+
+    /// Get the locales that have texts
+    fn locales(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Build the locale key for a column of a table row
+pub fn locale_key(table: &str, id: i32, column: &str) -> String {
+    format!("{}_{}_{}", table, id, column)
+}
+
+/// A [`LocaleProvider`] with a chosen locale and fallbacks
+pub struct Localizer<'p, P: ?Sized> {
+    provider: &'p P,
+    locale: String,
+}
+
+impl<'p, P: LocaleProvider + ?Sized> Localizer<'p, P> {
+    /// Create a localizer for a locale, e.g. `de_DE`
+    pub fn new(provider: &'p P, locale: &str) -> Self {
+        Self {
+            provider,
+            locale: locale.to_string(),
+        }
+    }
+
+    /// Get the chosen locale
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Get the text for a key, falling back to [`DEFAULT_LOCALE`]
+    pub fn text(&self, key: &str) -> Option<Cow<'p, str>> {
+        self.provider
+            .get(key, &self.locale)
+            .or_else(|| self.provider.get(key, DEFAULT_LOCALE))
+    }
+
+    /// Get a column of a row
+    ///
+    /// If `localize` is set, the text is looked up with [`locale_key`]. Otherwise,
+    /// or if there is no localized text, `raw` from the FDB is used.
+    pub fn column(
+        &self,
+        table: &str,
+        id: i32,
+        column: &str,
+        localize: bool,
+        raw: Option<&Latin1Str>,
+    ) -> Option<String> {
+        let localized = if localize {
+            self.text(&locale_key(table, id, column))
+        } else {
+            None
+        };
+        match localized {
+            Some(text) => Some(text.into_owned()),
+            None => raw
+                .filter(|s| !s.is_empty())
+                .map(|s| s.decode().into_owned()),
+        }
+    }
+
+    /// Get the name of an object
+    ///
+    /// Falls back to `displayName` and then `name`.
+    pub fn object_name(&self, db: &TypedDatabase, lot: i32) -> Option<String> {
+        let row = db.objects.key_iter(lot).next()?;
+        let raw = row.display_name().filter(|s| !s.is_empty());
+        self.column(
+            "Objects",
+            lot,
+            "name",
+            row.localize(),
+            raw.or(Some(row.name())),
+        )
+    }
+
+    /// Get the description of an object
+    pub fn object_description(&self, db: &TypedDatabase, lot: i32) -> Option<String> {
+        let row = db.objects.key_iter(lot).next()?;
+        self.column(
+            "Objects",
+            lot,
+            "description",
+            row.localize(),
+            row.description(),
+        )
+    }
+
+    /// Get the name of a mission
+    ///
+    /// `Missions` has no name column, so there is no fallback.
+    pub fn mission_name(&self, db: &TypedDatabase, id: i32) -> Option<String> {
+        let row = db.missions.key_iter(id).next()?;
+        self.column("Missions", id, "name", row.localize(), None)
+    }
+
+    /// Get the description of a mission, from `MissionText`
+    ///
+    /// `MissionText` has no description column, so there is no fallback.
+    pub fn mission_description(&self, db: &TypedDatabase, id: i32) -> Option<String> {
+        let row = db.mission_text.key_iter(id).next()?;
+        self.column("MissionText", id, "description", row.localize(), None)
+    }
+
+    /// Get the name of an item set
+    ///
+    /// Item sets only have a `kitName` key, there is no description to go with it.
+    pub fn item_set_name(&self, db: &TypedDatabase, id: i32) -> Option<String> {
+        let row = db.item_sets.key_iter(id).next()?;
+        self.column("ItemSets", id, "kitName", row.localize(), None)
+    }
+
+    /// Get the name of an emote
+    ///
+    /// Falls back to the chat `command`. Emotes have no description key.
+    pub fn emote_name(&self, db: &TypedDatabase, id: i32) -> Option<String> {
+        let row = db.emotes.key_iter(id).next()?;
+        self.column("Emotes", id, "outputText", row.localize(), row.command())
+    }
+
+    /// Get the name of a zone as shown to players
+    ///
+    /// `ZoneTable` has no separate name key; the client shows the text of the
+    /// `DisplayDescription` key. This falls back to the raw `DisplayDescription`
+    /// and then to the internal `zoneName`.
+    pub fn zone_name(&self, db: &TypedDatabase, id: i32) -> Option<String> {
+        let row = db.zone_table.key_iter(id).next()?;
+        let raw = row.display_description().filter(|s| !s.is_empty());
+        self.column(
+            "ZoneTable",
+            id,
+            "DisplayDescription",
+            row.localize(),
+            raw.or(Some(row.zone_name())),
+        )
+    }
+
+    /// Get the display description of a zone
+    ///
+    /// This is the same key as [`Localizer::zone_name`], without the fallback to
+    /// `zoneName`.
+    pub fn zone_description(&self, db: &TypedDatabase, id: i32) -> Option<String> {
+        let row = db.zone_table.key_iter(id).next()?;
+        self.column(
+            "ZoneTable",
+            id,
+            "DisplayDescription",
+            row.localize(),
+            row.display_description(),
+        )
+    }
+}
+
+#[cfg(feature = "quick-xml")]
+pub use self::xml::{LocaleXml, LocaleXmlError};
+
+#[cfg(feature = "quick-xml")]
+mod xml {
+    use std::{
+        borrow::Cow,
+        collections::HashMap,
+        error::Error,
+        fmt,
+        fs::File,
+        io::{self, BufRead, BufReader},
+        path::Path,
+    };
+
+    use quick_xml::{events::Event, Reader};
+
+    use super::LocaleProvider;
+
+    /// An error when loading a [`LocaleXml`]
+    #[derive(Debug)]
+    pub enum LocaleXmlError {
+        /// The file could not be opened
+        Io(io::Error),
+        /// The file is not valid XML
+        Xml(quick_xml::Error),
+    }
+
+    impl fmt::Display for LocaleXmlError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::Io(e) => write!(f, "failed to open locale file: {}", e),
+                Self::Xml(e) => write!(f, "failed to parse locale file: {}", e),
+            }
+        }
+    }
+
+    impl Error for LocaleXmlError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                Self::Io(e) => Some(e),
+                Self::Xml(e) => Some(e),
+            }
+        }
+    }
+
+    impl From<io::Error> for LocaleXmlError {
+        fn from(e: io::Error) -> Self {
+            Self::Io(e)
+        }
+    }
+
+    impl From<quick_xml::Error> for LocaleXmlError {
+        fn from(e: quick_xml::Error) -> Self {
+            Self::Xml(e)
+        }
+    }
+
+    /// The texts of a `locale.xml` file
+    ///
+    /// The file has a `<locales>` list and `<phrase id="...">` elements with one
+    /// `<translation locale="...">` per locale.
+    #[derive(Debug, Clone, Default)]
+    pub struct LocaleXml {
+        locales: Vec<String>,
+        phrases: HashMap<String, HashMap<String, String>>,
+    }
+
+    impl LocaleXml {
+        /// Load a locale file from disk
+        pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, LocaleXmlError> {
+            let file = File::open(path)?;
+            Self::from_reader(BufReader::new(file))
+        }
+
+        /// Load a locale file from a reader
+        pub fn from_reader<R: BufRead>(reader: R) -> Result<Self, LocaleXmlError> {
+            let mut reader = Reader::from_reader(reader);
+            let mut result = Self::default();
+            let mut buf = Vec::new();
+            let mut phrase: Option<String> = None;
+            let mut translation: Option<String> = None;
+            let mut in_locale = false;
+            let mut text = String::new();
+
+            loop {
+                match reader.read_event_into(&mut buf)? {
+                    Event::Start(e) => match e.name().as_ref() {
+                        b"locale" => {
+                            in_locale = true;
+                            text.clear();
+                        }
+                        b"phrase" => {
+                            phrase = match e.try_get_attribute("id")? {
+                                Some(attr) => Some(attr.unescape_value()?.into_owned()),
+                                None => None,
+                            };
+                        }
+                        b"translation" => {
+                            translation = match e.try_get_attribute("locale")? {
+                                Some(attr) => Some(attr.unescape_value()?.into_owned()),
+                                None => None,
+                            };
+                            text.clear();
+                        }
+                        _ => {}
+                    },
+                    Event::Text(e) if in_locale || translation.is_some() => {
+                        text.push_str(&e.unescape()?);
+                    }
+                    Event::CData(e) if in_locale || translation.is_some() => {
+                        text.push_str(&String::from_utf8_lossy(&e));
+                    }
+                    Event::End(e) => match e.name().as_ref() {
+                        b"locale" => {
+                            in_locale = false;
+                            result.locales.push(text.trim().to_string());
+                        }
+                        b"phrase" => phrase = None,
+                        b"translation" => {
+                            if let (Some(id), Some(locale)) = (&phrase, translation.take()) {
+                                result
+                                    .phrases
+                                    .entry(id.clone())
+                                    .or_default()
+                                    .insert(locale, std::mem::take(&mut text));
+                            }
+                        }
+                        _ => {}
+                    },
+                    Event::Eof => break,
+                    _ => {}
+                }
+                buf.clear();
+            }
+            Ok(result)
+        }
+
+        /// Get the number of phrases
+        pub fn len(&self) -> usize {
+            self.phrases.len()
+        }
+
+        /// Whether there are no phrases
+        pub fn is_empty(&self) -> bool {
+            self.phrases.is_empty()
+        }
+    }
+
+    impl LocaleProvider for LocaleXml {
+        fn get(&self, key: &str, locale: &str) -> Option<Cow<'_, str>> {
+            self.phrases
+                .get(key)?
+                .get(locale)
+                .map(|s| Cow::Borrowed(s.as_str()))
+        }
+
+        fn locales(&self) -> Vec<String> {
+            self.locales.clone()
+        }
+    }
+}
+
+#[cfg(all(test, feature = "quick-xml"))]
+mod tests {
+    use super::{LocaleProvider, LocaleXml, Localizer};
+
+    const LOCALE_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<localization version="1.2">
+    <locales count="2">
+        <locale>en_US</locale>
+        <locale>de_DE</locale>
+    </locales>
+    <phrases count="2">
+        <phrase id="Objects_1_name">
+            <translation locale="en_US">Brick &amp; Mortar</translation>
+            <translation locale="de_DE">Stein &lt;3&gt;</translation>
+        </phrase>
+        <phrase id="Missions_2_name">
+            <translation locale="en_US">Only English</translation>
+        </phrase>
+    </phrases>
+</localization>"#;
+
+    #[test]
+    fn test_from_reader() {
+        let xml = LocaleXml::from_reader(LOCALE_XML.as_bytes()).unwrap();
+        assert_eq!(xml.locales(), vec!["en_US", "de_DE"]);
+        assert_eq!(xml.len(), 2);
+        assert_eq!(
+            xml.get("Objects_1_name", "en_US").as_deref(),
+            Some("Brick & Mortar")
+        );
+        assert_eq!(
+            xml.get("Objects_1_name", "de_DE").as_deref(),
+            Some("Stein <3>")
+        );
+        assert_eq!(xml.get("Missions_2_name", "de_DE"), None);
+        assert_eq!(xml.get("Missions_3_name", "en_US"), None);
+    }
+
+    #[test]
+    fn test_localizer_fallback() {
+        let xml = LocaleXml::from_reader(LOCALE_XML.as_bytes()).unwrap();
+        let de = Localizer::new(&xml, "de_DE");
+        assert_eq!(de.text("Objects_1_name").as_deref(), Some("Stein <3>"));
+        assert_eq!(de.text("Missions_2_name").as_deref(), Some("Only English"));
+        assert_eq!(de.text("Missions_3_name"), None);
+    }
+}
@@ -0,0 +1,87 @@
+//! # Loot matrices
+//!
+//! A `LootMatrixIndex` selects a list of rows in `LootMatrix`, each of which points
+//! to a group of items in `LootTable` (by `LootTableIndex`).
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::TypedDatabase;
+
+/// An item in a loot table
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LootItem {
+    /// The LOT of the item
+    pub lot: i32,
+    /// Whether the item only drops for a mission
+    pub mission_drop: bool,
+    /// The sort order of the item (e.g. in vendors)
+    pub sort_priority: i32,
+}
+
+/// An entry in the `LootMatrix` table with its items
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LootMatrixEntry {
+    /// The loot table to pick items from
+    pub loot_table_index: i32,
+    /// The rarity table to pick rarities from
+    pub rarity_table_index: i32,
+    /// The chance that this entry drops anything
+    pub percent: f32,
+    /// The minimum number of items
+    pub min_to_drop: i32,
+    /// The maximum number of items
+    pub max_to_drop: i32,
+    /// The player flag required for this entry
+    pub flag_id: Option<i32>,
+    /// The items in the loot table
+    pub items: Vec<LootItem>,
+}
+
+impl<'db> TypedDatabase<'db> {
+    /// Get the items of some loot tables, by `LootTableIndex`
+    ///
+    /// `LootTable` is keyed by item, so this scans the whole table once.
+    pub fn get_loot_table_items(&self, indices: &BTreeSet<i32>) -> BTreeMap<i32, Vec<LootItem>> {
+        let mut map: BTreeMap<i32, Vec<LootItem>> = BTreeMap::new();
+        for row in self.loot_table.row_iter() {
+            let index = row.loot_table_index();
+            if indices.contains(&index) {
+                map.entry(index).or_default().push(LootItem {
+                    lot: row.itemid(),
+                    mission_drop: row.mission_drop(),
+                    sort_priority: row.sort_priority(),
+                });
+            }
+        }
+        map
+    }
+
+    /// Get all entries of a loot matrix with their items
+    pub fn get_loot_matrix(&self, loot_matrix_index: i32) -> Vec<LootMatrixEntry> {
+        let mut entries: Vec<LootMatrixEntry> = self
+            .loot_matrix
+            .key_iter(loot_matrix_index)
+            .map(|row| LootMatrixEntry {
+                loot_table_index: row.loot_table_index(),
+                rarity_table_index: row.rarity_table_index(),
+                percent: row.percent(),
+                min_to_drop: row.min_to_drop(),
+                max_to_drop: row.max_to_drop(),
+                flag_id: row.flag_id(),
+                items: Vec::new(),
+            })
+            .collect();
+        let indices = entries.iter().map(|e| e.loot_table_index).collect();
+        let items = self.get_loot_table_items(&indices);
+        for entry in &mut entries {
+            if let Some(list) = items.get(&entry.loot_table_index) {
+                entry.items = list.clone();
+            }
+        }
+        entries
+    }
+}
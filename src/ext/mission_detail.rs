@@ -0,0 +1,396 @@
+//! # Full mission details
+//!
+//! A [`MissionDetail`] combines the `Missions`, `MissionTasks`, `MissionText` and
+//! `MissionEmail` rows of a single mission.
+
+use latin1str::Latin1Str;
+use serde::Serialize;
+
+use super::{
+    missions::{MissionRewards, PrereqExpr},
+    parse_id_list, MissionKind,
+};
+use crate::{
+    rows::{MissionEmailRow, MissionTasksRow, MissionTextRow},
+    TypedDatabase,
+};
+
+/// The type of a mission task (`MissionTasks.taskType`)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize)]
+pub enum MissionTaskType {
+    /// Smash an object
+    Smash,
+    /// Complete a scripted task
+    Script,
+    /// Complete an activity
+    Activity,
+    /// Collect collectibles
+    Collection,
+    /// Talk to an NPC
+    TalkToNpc,
+    /// Use an emote
+    Emote,
+    /// Complete a smash chain
+    SmashChain,
+    /// Buy an item from a vendor
+    Buy,
+    /// Sell an item to a vendor
+    Sell,
+    /// Use an item
+    UseItem,
+    /// Use a skill
+    UseSkill,
+    /// Gather items
+    Gather,
+    /// Explore a location
+    Explore,
+    /// Deliver an item
+    Delivery,
+    /// Perform an activity
+    PerformActivity,
+    /// Interact with an object
+    Interact,
+    /// Complete other missions
+    Meta,
+    /// Earn reputation
+    EarnReputation,
+    /// Vote on a model
+    Voting,
+    /// Deliver a model to a showcase
+    ShowcaseDelivery,
+    /// Revive a player
+    ReviveCast,
+    /// Collect a powerup
+    Powerup,
+    /// Tame a pet
+    PetTaming,
+    /// Complete a race
+    Racing,
+    /// Set a player flag
+    PlayerFlag,
+    /// Place a model on a property
+    PlaceModel,
+    /// Remove a model from a property
+    RemoveModel,
+    /// Add a behavior to a model
+    AddBehavior,
+    /// Remove a behavior from a model
+    RemoveBehavior,
+    /// Claim a property
+    ClaimProperty,
+    /// Visit a property
+    VisitProperty,
+    /// Play for some time
+    TimePlayed,
+    /// Donate items
+    Donation,
+    /// A task type not known to this crate
+    Unknown(i32),
+}
+
+impl From<i32> for MissionTaskType {
+    fn from(value: i32) -> Self {
+        match value {
+            0 => Self::Smash,
+            1 => Self::Script,
+            2 => Self::Activity,
+            3 => Self::Collection,
+            4 => Self::TalkToNpc,
+            5 => Self::Emote,
+            6 => Self::SmashChain,
+            7 => Self::Buy,
+            8 => Self::Sell,
+            9 => Self::UseItem,
+            10 => Self::UseSkill,
+            11 => Self::Gather,
+            12 => Self::Explore,
+            13 => Self::Delivery,
+            14 => Self::PerformActivity,
+            15 => Self::Interact,
+            16 => Self::Meta,
+            17 => Self::EarnReputation,
+            18 => Self::Voting,
+            19 => Self::ShowcaseDelivery,
+            20 => Self::ReviveCast,
+            21 => Self::Powerup,
+            22 => Self::PetTaming,
+            23 => Self::Racing,
+            24 => Self::PlayerFlag,
+            25 => Self::PlaceModel,
+            26 => Self::RemoveModel,
+            27 => Self::AddBehavior,
+            28 => Self::RemoveBehavior,
+            29 => Self::ClaimProperty,
+            30 => Self::VisitProperty,
+            31 => Self::TimePlayed,
+            32 => Self::Donation,
+            _ => Self::Unknown(value),
+        }
+    }
+}
+
+/// A single task of a mission
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissionTaskDetail<'a> {
+    /// The unique ID of the task
+    pub uid: i32,
+    /// The type of the task
+    pub task_type: MissionTaskType,
+    /// All targets of the task, from `target` and `targetGroup`
+    pub targets: Vec<i32>,
+    /// The number of times the task needs to be done
+    pub target_value: Option<i32>,
+    /// The first task parameter
+    pub task_param1: Option<&'a Latin1Str>,
+    /// The icon ID of the task
+    pub icon_id: i32,
+    /// The large icon ID of the task
+    pub large_task_icon_id: Option<i32>,
+    /// The large icon path of the task
+    pub large_task_icon: Option<&'a Latin1Str>,
+}
+
+impl<'a, 'b> From<MissionTasksRow<'a, 'b>> for MissionTaskDetail<'a> {
+    fn from(row: MissionTasksRow<'a, 'b>) -> Self {
+        let mut targets: Vec<i32> = row.target().into_iter().collect();
+        if let Some(group) = row.target_group() {
+            for id in parse_id_list(group) {
+                if !targets.contains(&id) {
+                    targets.push(id);
+                }
+            }
+        }
+        Self {
+            uid: row.uid(),
+            task_type: MissionTaskType::from(row.task_type()),
+            targets,
+            target_value: row.target_value(),
+            task_param1: row.task_param1(),
+            icon_id: row.icon_id(),
+            large_task_icon_id: row.large_task_icon_id(),
+            large_task_icon: row.large_task_icon(),
+        }
+    }
+}
+
+/// A cinematic with its lead-in time
+#[derive(Debug, Copy, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cinematic<'a> {
+    /// The name of the cinematic
+    pub name: &'a Latin1Str,
+    /// The lead-in time in seconds
+    pub leadin: Option<f32>,
+}
+
+impl<'a> Cinematic<'a> {
+    fn new(name: Option<&'a Latin1Str>, leadin: Option<f32>) -> Option<Self> {
+        name.filter(|n| !n.is_empty())
+            .map(|name| Cinematic { name, leadin })
+    }
+}
+
+/// Presentation data of a mission from `MissionText`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissionTextDetail<'a> {
+    /// `story_icon`
+    pub story_icon: Option<&'a Latin1Str>,
+    /// `missionIcon`
+    pub mission_icon: Option<&'a Latin1Str>,
+    /// `offerNPCIcon`
+    pub offer_npc_icon: Option<&'a Latin1Str>,
+    /// `IconID`
+    pub icon_id: Option<i32>,
+    /// `turnInIconID`
+    pub turn_in_icon_id: Option<i32>,
+    /// `state_1_anim` to `state_4_anim`
+    pub state_anims: [Option<&'a Latin1Str>; 4],
+    /// `state_3_turnin_anim`
+    pub state_3_turnin_anim: Option<&'a Latin1Str>,
+    /// `state_4_turnin_anim`
+    pub state_4_turnin_anim: Option<&'a Latin1Str>,
+    /// `onclick_anim`
+    pub onclick_anim: Option<&'a Latin1Str>,
+    /// `CinematicAccepted`
+    pub cinematic_accepted: Option<Cinematic<'a>>,
+    /// `CinematicCompleted`
+    pub cinematic_completed: Option<Cinematic<'a>>,
+    /// `CinematicRepeatable`
+    pub cinematic_repeatable: Option<Cinematic<'a>>,
+    /// `CinematicRepeatableCompleted`
+    pub cinematic_repeatable_completed: Option<Cinematic<'a>>,
+    /// `AudioEventGUID_Interact`
+    pub audio_interact: Option<&'a Latin1Str>,
+    /// `AudioEventGUID_OfferAccept`
+    pub audio_offer_accept: Option<&'a Latin1Str>,
+    /// `AudioEventGUID_OfferDeny`
+    pub audio_offer_deny: Option<&'a Latin1Str>,
+    /// `AudioEventGUID_Completed`
+    pub audio_completed: Option<&'a Latin1Str>,
+    /// `AudioEventGUID_TurnIn`
+    pub audio_turn_in: Option<&'a Latin1Str>,
+    /// `AudioEventGUID_Failed`
+    pub audio_failed: Option<&'a Latin1Str>,
+    /// `AudioEventGUID_Progress`
+    pub audio_progress: Option<&'a Latin1Str>,
+    /// `AudioMusicCue_OfferAccept`
+    pub music_cue_offer_accept: Option<&'a Latin1Str>,
+    /// `AudioMusicCue_TurnIn`
+    pub music_cue_turn_in: Option<&'a Latin1Str>,
+}
+
+impl<'a, 'b> From<MissionTextRow<'a, 'b>> for MissionTextDetail<'a> {
+    fn from(row: MissionTextRow<'a, 'b>) -> Self {
+        Self {
+            story_icon: row.story_icon(),
+            mission_icon: row.mission_icon(),
+            offer_npc_icon: row.offer_npc_icon(),
+            icon_id: row.icon_id(),
+            turn_in_icon_id: row.turn_in_icon_id(),
+            state_anims: [
+                row.state_1_anim(),
+                row.state_2_anim(),
+                row.state_3_anim(),
+                row.state_4_anim(),
+            ],
+            state_3_turnin_anim: row.state_3_turnin_anim(),
+            state_4_turnin_anim: row.state_4_turnin_anim(),
+            onclick_anim: row.onclick_anim(),
+            cinematic_accepted: Cinematic::new(
+                row.cinematic_accepted(),
+                row.cinematic_accepted_leadin(),
+            ),
+            cinematic_completed: Cinematic::new(
+                row.cinematic_completed(),
+                row.cinematic_completed_leadin(),
+            ),
+            cinematic_repeatable: Cinematic::new(
+                row.cinematic_repeatable(),
+                row.cinematic_repeatable_leadin(),
+            ),
+            cinematic_repeatable_completed: Cinematic::new(
+                row.cinematic_repeatable_completed(),
+                row.cinematic_repeatable_completed_leadin(),
+            ),
+            audio_interact: row.audio_event_guid_interact(),
+            audio_offer_accept: row.audio_event_guid_offer_accept(),
+            audio_offer_deny: row.audio_event_guid_offer_deny(),
+            audio_completed: row.audio_event_guid_completed(),
+            audio_turn_in: row.audio_event_guid_turn_in(),
+            audio_failed: row.audio_event_guid_failed(),
+            audio_progress: row.audio_event_guid_progress(),
+            music_cue_offer_accept: row.audio_music_cue_offer_accept(),
+            music_cue_turn_in: row.audio_music_cue_turn_in(),
+        }
+    }
+}
+
+/// An email sent in relation to a mission
+#[derive(Debug, Copy, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissionEmailDetail {
+    /// The ID of the email
+    pub id: i32,
+    /// `messageType`
+    pub message_type: i32,
+    /// `notificationGroup`
+    pub notification_group: i32,
+    /// The LOT of the attached item
+    pub attachment_lot: Option<i32>,
+}
+
+impl<'a, 'b> From<MissionEmailRow<'a, 'b>> for MissionEmailDetail {
+    fn from(row: MissionEmailRow<'a, 'b>) -> Self {
+        Self {
+            id: row.id(),
+            message_type: row.message_type(),
+            notification_group: row.notification_group(),
+            attachment_lot: row.attachment_lot(),
+        }
+    }
+}
+
+/// All data for a single mission
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissionDetail<'a> {
+    /// The ID of the mission
+    pub id: i32,
+    /// Whether this is a mission or an achievement
+    pub kind: MissionKind,
+    /// `defined_type`
+    pub defined_type: &'a Latin1Str,
+    /// `defined_subtype`
+    pub defined_subtype: Option<&'a Latin1Str>,
+    /// `UISortOrder`
+    pub ui_sort_order: Option<i32>,
+    /// The LOT of the NPC that offers the mission
+    pub offer_object_id: i32,
+    /// The LOT of the NPC that completes the mission
+    pub target_object_id: i32,
+    /// Whether the mission can be repeated
+    pub repeatable: bool,
+    /// The cooldown before the mission can be repeated
+    pub cooldown_time: Option<i64>,
+    /// The time limit of the mission
+    pub time_limit: Option<i32>,
+    /// The icon ID of the mission
+    pub mission_icon_id: Option<i32>,
+    /// The parsed prerequisites
+    pub prereqs: PrereqExpr,
+    /// The rewards of the mission
+    pub rewards: MissionRewards,
+    /// The tasks of the mission
+    pub tasks: Vec<MissionTaskDetail<'a>>,
+    /// The `MissionText` entry
+    pub text: Option<MissionTextDetail<'a>>,
+    /// The `MissionEmail` entries
+    pub emails: Vec<MissionEmailDetail>,
+}
+
+impl<'a> TypedDatabase<'a> {
+    /// Get all data for the specified mission ID
+    ///
+    /// Prerequisites that fail to parse are logged and treated as empty.
+    pub fn get_mission_detail(&self, id: i32) -> Option<MissionDetail<'_>> {
+        let row = self.missions.key_iter(id).next()?;
+        let prereqs = row.prereqs_or_none();
+        let emails = match &self.mission_email {
+            Some(table) => table
+                .row_iter()
+                .filter(|e| e.mission_id() == id)
+                .map(MissionEmailDetail::from)
+                .collect(),
+            None => Vec::new(),
+        };
+
+        Some(MissionDetail {
+            id,
+            kind: MissionKind::from_is_mission(row.is_mission()),
+            defined_type: row.defined_type(),
+            defined_subtype: row.defined_subtype(),
+            ui_sort_order: row.ui_sort_order(),
+            offer_object_id: row.offer_object_id(),
+            target_object_id: row.target_object_id(),
+            repeatable: row.repeatable(),
+            cooldown_time: row.cooldown_time(),
+            time_limit: row.time_limit(),
+            mission_icon_id: row.mission_icon_id(),
+            prereqs,
+            rewards: row.rewards(),
+            tasks: self
+                .mission_tasks
+                .key_iter(id)
+                .map(MissionTaskDetail::from)
+                .collect(),
+            text: self
+                .mission_text
+                .key_iter(id)
+                .next()
+                .map(MissionTextDetail::from),
+            emails,
+        })
+    }
+}
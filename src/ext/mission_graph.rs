@@ -0,0 +1,454 @@
+//! # Mission dependency graph
+//!
+//! A [`MissionGraph`] contains every entry of the `Missions` table together with its
+//! parsed prerequisites. Edges point from a prerequisite to the mission it unlocks.
+//!
+//! Some prerequisites reference missions that are not in the table. These edges
+//! are kept, so the `from` side of a [`MissionEdge`] may not be a node of the graph.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Write},
+};
+
+use serde::{ser::SerializeStruct, Serialize};
+
+use super::{
+    missions::{MissionProgress, PrereqExpr},
+    MissionKind,
+};
+use crate::TypedDatabase;
+
+/// A single entry in the [`MissionGraph`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissionNode {
+    /// The ID of the mission
+    pub id: i32,
+    /// Whether this is a mission or an achievement
+    pub kind: MissionKind,
+    /// The `defined_type` of the mission
+    pub defined_type: String,
+    /// The `defined_subtype` of the mission
+    pub defined_subtype: Option<String>,
+    /// Whether the mission can be repeated
+    pub repeatable: bool,
+    /// The parsed prerequisites
+    pub prereqs: PrereqExpr,
+}
+
+/// An edge in the [`MissionGraph`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissionEdge {
+    /// The prerequisite mission
+    pub from: i32,
+    /// The mission that depends on `from`
+    pub to: i32,
+    /// Whether `from` is only one of several alternatives (i.e. below an `|`)
+    pub alternative: bool,
+    /// The state `from` needs to reach, if not "complete"
+    pub state: Option<i32>,
+}
+
+/// The dependency graph of all missions
+#[derive(Debug, Clone, Default)]
+pub struct MissionGraph {
+    nodes: BTreeMap<i32, MissionNode>,
+    edges: Vec<MissionEdge>,
+    dependents: BTreeMap<i32, BTreeSet<i32>>,
+}
+
+fn collect_edges(expr: &PrereqExpr, to: i32, alternative: bool, edges: &mut Vec<MissionEdge>) {
+    match expr {
+        PrereqExpr::Mission { id, state } => edges.push(MissionEdge {
+            from: *id,
+            to,
+            alternative,
+            state: *state,
+        }),
+        PrereqExpr::All(list) => {
+            for e in list {
+                collect_edges(e, to, alternative, edges);
+            }
+        }
+        PrereqExpr::Any(list) => {
+            let alternative = alternative || list.len() > 1;
+            for e in list {
+                collect_edges(e, to, alternative, edges);
+            }
+        }
+    }
+}
+
+/// Escape a string for use in a quoted DOT label
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Progress of a player with one additional completed mission
+struct WithCompleted<'a, P: ?Sized> {
+    inner: &'a P,
+    id: i32,
+}
+
+impl<'a, P: MissionProgress + ?Sized> MissionProgress for WithCompleted<'a, P> {
+    fn is_completed(&self, mission_id: i32) -> bool {
+        mission_id == self.id || self.inner.is_completed(mission_id)
+    }
+
+    fn has_reached_state(&self, mission_id: i32, state: i32) -> bool {
+        mission_id == self.id || self.inner.has_reached_state(mission_id, state)
+    }
+}
+
+impl MissionGraph {
+    /// Build the graph from the `Missions` table
+    ///
+    /// Prerequisites that fail to parse are logged and treated as empty.
+    pub fn new(db: &TypedDatabase) -> Self {
+        let mut graph = Self::default();
+        for row in db.missions.row_iter() {
+            let id = row.id();
+            let prereqs = row.prereqs_or_none();
+            graph.insert(MissionNode {
+                id,
+                kind: MissionKind::from_is_mission(row.is_mission()),
+                defined_type: row.defined_type().decode().into_owned(),
+                defined_subtype: row.defined_subtype().map(|s| s.decode().into_owned()),
+                repeatable: row.repeatable(),
+                prereqs,
+            });
+        }
+        graph
+    }
+
+    fn insert(&mut self, node: MissionNode) {
+        let start = self.edges.len();
+        collect_edges(&node.prereqs, node.id, false, &mut self.edges);
+        for edge in &self.edges[start..] {
+            self.dependents
+                .entry(edge.from)
+                .or_default()
+                .insert(edge.to);
+        }
+        self.nodes.insert(node.id, node);
+    }
+
+    /// Get a single node
+    pub fn get(&self, id: i32) -> Option<&MissionNode> {
+        self.nodes.get(&id)
+    }
+
+    /// Iterate over all nodes, ordered by ID
+    pub fn nodes(&self) -> impl Iterator<Item = &MissionNode> {
+        self.nodes.values()
+    }
+
+    /// Get all edges
+    ///
+    /// This includes edges from prerequisites that are not a node of the graph.
+    pub fn edges(&self) -> &[MissionEdge] {
+        &self.edges
+    }
+
+    /// Get the missions directly referenced in the prerequisites of `id`
+    pub fn prerequisites(&self, id: i32) -> BTreeSet<i32> {
+        self.nodes
+            .get(&id)
+            .map(|node| node.prereqs.mission_ids())
+            .unwrap_or_default()
+    }
+
+    /// Get all missions that `id` depends on, directly or indirectly
+    pub fn transitive_prerequisites(&self, id: i32) -> BTreeSet<i32> {
+        self.transitive(id, |g, n| g.prerequisites(n))
+    }
+
+    /// Get the missions that directly reference `id` in their prerequisites
+    pub fn dependents(&self, id: i32) -> BTreeSet<i32> {
+        self.dependents.get(&id).cloned().unwrap_or_default()
+    }
+
+    /// Get all missions that depend on `id`, directly or indirectly
+    pub fn transitive_dependents(&self, id: i32) -> BTreeSet<i32> {
+        self.transitive(id, |g, n| g.dependents(n))
+    }
+
+    fn transitive<F>(&self, id: i32, next: F) -> BTreeSet<i32>
+    where
+        F: Fn(&Self, i32) -> BTreeSet<i32>,
+    {
+        let mut seen = BTreeSet::new();
+        let mut stack = vec![id];
+        while let Some(n) = stack.pop() {
+            for m in next(self, n) {
+                if seen.insert(m) {
+                    stack.push(m);
+                }
+            }
+        }
+        seen
+    }
+
+    /// Get the missions whose prerequisites become satisfied by completing `id`
+    ///
+    /// `progress` is the progress of the player *before* completing `id`. Missions
+    /// that were already available before are not included.
+    pub fn unlocked_by<P: MissionProgress + ?Sized>(&self, id: i32, progress: &P) -> Vec<i32> {
+        let after = WithCompleted {
+            inner: progress,
+            id,
+        };
+        self.dependents(id)
+            .into_iter()
+            .filter_map(|m| self.nodes.get(&m))
+            .filter(|node| !node.prereqs.is_satisfied(progress))
+            .filter(|node| node.prereqs.is_satisfied(&after))
+            .map(|node| node.id)
+            .collect()
+    }
+
+    /// Check whether there is a cycle in the prerequisites
+    pub fn has_cycle(&self) -> bool {
+        self.find_cycle().is_some()
+    }
+
+    /// Find a cycle in the prerequisites, if there is one
+    ///
+    /// The returned list starts and ends with the same mission.
+    pub fn find_cycle(&self) -> Option<Vec<i32>> {
+        // 1: on the current path, 2: done
+        let mut state: BTreeMap<i32, u8> = BTreeMap::new();
+        for &root in self.nodes.keys() {
+            if state.contains_key(&root) {
+                continue;
+            }
+            let mut path = vec![root];
+            let mut stack = vec![self.prerequisites(root).into_iter()];
+            state.insert(root, 1);
+            while let Some(iter) = stack.last_mut() {
+                match iter.next() {
+                    Some(next) => match state.get(&next) {
+                        Some(1) => {
+                            let start = path.iter().position(|&n| n == next).unwrap();
+                            let mut cycle = path[start..].to_vec();
+                            cycle.push(next);
+                            return Some(cycle);
+                        }
+                        Some(_) => {}
+                        None => {
+                            state.insert(next, 1);
+                            path.push(next);
+                            stack.push(self.prerequisites(next).into_iter());
+                        }
+                    },
+                    None => {
+                        stack.pop();
+                        if let Some(done) = path.pop() {
+                            state.insert(done, 2);
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Create a graph that only contains the given missions
+    ///
+    /// Only edges between two included missions are kept.
+    pub fn subgraph<I: IntoIterator<Item = i32>>(&self, ids: I) -> Self {
+        let ids: BTreeSet<i32> = ids.into_iter().collect();
+        let mut graph = Self::default();
+        for id in &ids {
+            if let Some(node) = self.nodes.get(id) {
+                graph.nodes.insert(*id, node.clone());
+            }
+        }
+        for edge in &self.edges {
+            if ids.contains(&edge.from) && ids.contains(&edge.to) {
+                graph.edges.push(*edge);
+                graph
+                    .dependents
+                    .entry(edge.from)
+                    .or_default()
+                    .insert(edge.to);
+            }
+        }
+        graph
+    }
+
+    /// Create a graph with `id` and all its transitive prerequisites and dependents
+    pub fn chain(&self, id: i32) -> Self {
+        let mut ids = self.transitive_prerequisites(id);
+        ids.extend(self.transitive_dependents(id));
+        ids.insert(id);
+        self.subgraph(ids)
+    }
+
+    /// Write the graph in Graphviz DOT format
+    ///
+    /// Missions are drawn as boxes, achievements as ellipses. Edges to alternative
+    /// prerequisites are dashed.
+    pub fn write_dot<W: Write>(&self, w: &mut W) -> fmt::Result {
+        writeln!(w, "digraph missions {{")?;
+        for node in self.nodes.values() {
+            let shape = match node.kind {
+                MissionKind::Mission => "box",
+                MissionKind::Achievement => "ellipse",
+            };
+            let mut label = escape_dot(&node.defined_type);
+            if let Some(subtype) = node.defined_subtype.as_deref().filter(|s| !s.is_empty()) {
+                write!(label, " / {}", escape_dot(subtype))?;
+            }
+            writeln!(
+                w,
+                "    m{} [shape={}, label=\"#{}\\n{}\"];",
+                node.id, shape, node.id, label
+            )?;
+        }
+        for edge in &self.edges {
+            write!(w, "    m{} -> m{}", edge.from, edge.to)?;
+            match (edge.alternative, edge.state) {
+                (false, None) => writeln!(w, ";")?,
+                (true, None) => writeln!(w, " [style=dashed];")?,
+                (false, Some(state)) => writeln!(w, " [label=\"{}\"];", state)?,
+                (true, Some(state)) => writeln!(w, " [style=dashed, label=\"{}\"];", state)?,
+            }
+        }
+        writeln!(w, "}}")
+    }
+
+    /// Return the graph in Graphviz DOT format
+    pub fn to_dot(&self) -> String {
+        let mut out = String::new();
+        self.write_dot(&mut out).unwrap();
+        out
+    }
+}
+
+impl Serialize for MissionGraph {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let nodes: Vec<&MissionNode> = self.nodes.values().collect();
+        let mut s = serializer.serialize_struct("MissionGraph", 2)?;
+        s.serialize_field("nodes", &nodes)?;
+        s.serialize_field("edges", &self.edges)?;
+        s.end()
+    }
+}
+
+impl<'db> TypedDatabase<'db> {
+    /// Build the dependency graph of all missions
+    pub fn mission_graph(&self) -> MissionGraph {
+        MissionGraph::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MissionGraph, MissionKind, MissionNode, PrereqExpr};
+    use crate::ext::test_util::ids;
+
+    /// 1 -> 2 -> 4, 2|5 -> 3 -> 4 and the dangling 99 -> 6
+    fn sample() -> MissionGraph {
+        let mut graph = MissionGraph::default();
+        for &(id, prereqs) in &[
+            (1, ""),
+            (2, "1"),
+            (3, "2|5"),
+            (4, "2,3"),
+            (5, ""),
+            (6, "99"),
+        ] {
+            graph.insert(MissionNode {
+                id,
+                kind: MissionKind::Mission,
+                defined_type: "Test".to_owned(),
+                defined_subtype: None,
+                repeatable: false,
+                prereqs: PrereqExpr::parse(prereqs).unwrap(),
+            });
+        }
+        graph
+    }
+
+    #[test]
+    fn test_transitive() {
+        let graph = sample();
+        assert_eq!(graph.prerequisites(4), ids(&[2, 3]));
+        assert_eq!(graph.transitive_prerequisites(4), ids(&[1, 2, 3, 5]));
+        assert_eq!(graph.dependents(2), ids(&[3, 4]));
+        assert_eq!(graph.transitive_dependents(1), ids(&[2, 3, 4]));
+        assert_eq!(graph.transitive_prerequisites(1), ids(&[]));
+        assert_eq!(graph.transitive_prerequisites(6), ids(&[99]));
+        assert!(graph.get(99).is_none());
+    }
+
+    #[test]
+    fn test_unlocked_by() {
+        let graph = sample();
+        assert_eq!(graph.unlocked_by(1, &ids(&[])), vec![2]);
+        assert_eq!(graph.unlocked_by(2, &ids(&[1])), vec![3]);
+        assert_eq!(graph.unlocked_by(3, &ids(&[1, 2])), vec![4]);
+        // 3 was already available through 5
+        assert_eq!(graph.unlocked_by(2, &ids(&[1, 5])), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let mut graph = sample();
+        assert_eq!(graph.find_cycle(), None);
+        assert!(!graph.has_cycle());
+
+        graph.insert(MissionNode {
+            id: 1,
+            kind: MissionKind::Mission,
+            defined_type: "Test".to_owned(),
+            defined_subtype: None,
+            repeatable: false,
+            prereqs: PrereqExpr::parse("4").unwrap(),
+        });
+        let cycle = graph.find_cycle().unwrap();
+        assert_eq!(cycle.first(), cycle.last());
+        assert!(cycle.contains(&1) && cycle.contains(&4));
+        for pair in cycle.windows(2) {
+            assert!(graph.prerequisites(pair[0]).contains(&pair[1]));
+        }
+    }
+
+    #[test]
+    fn test_chain_and_subgraph() {
+        let graph = sample();
+        let chain = graph.chain(3);
+        let nodes: Vec<i32> = chain.nodes().map(|n| n.id).collect();
+        assert_eq!(nodes, vec![1, 2, 3, 4, 5]);
+        assert_eq!(chain.edges().len(), 5);
+
+        let sub = graph.subgraph(vec![2, 4, 6]);
+        let edges: Vec<(i32, i32)> = sub.edges().iter().map(|e| (e.from, e.to)).collect();
+        assert_eq!(edges, vec![(2, 4)]);
+        assert_eq!(sub.dependents(2), ids(&[4]));
+        assert!(sub.edges().iter().all(|e| sub.get(e.from).is_some()));
+    }
+
+    #[test]
+    fn test_dot_escaping() {
+        let mut graph = MissionGraph::default();
+        graph.insert(MissionNode {
+            id: 1,
+            kind: MissionKind::Achievement,
+            defined_type: "a\\\"b".to_owned(),
+            defined_subtype: Some("c\\n".to_owned()),
+            repeatable: false,
+            prereqs: PrereqExpr::NONE,
+        });
+        assert_eq!(
+            graph.to_dot(),
+            "digraph missions {\n    m1 [shape=ellipse, label=\"#1\\na\\\\\\\"b / c\\\\n\"];\n}\n"
+        );
+    }
+}
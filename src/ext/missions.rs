@@ -0,0 +1,529 @@
+//! # Mission data
+//!
+//! ## Prerequisites
+//!
+//! The `prereqMissionID` column of the `Missions` table contains a small expression
+//! language over mission IDs:
+//!
+//! - `|` means *any of*
+//! - `,` and `&` both mean *all of* and bind tighter than `|`
+//! - parentheses group sub-expressions
+//! - a `:state` suffix requires the mission to have reached at least that state
+//!   instead of being complete
+//!
+//! An empty (or missing) expression is always satisfied.
+//!
+//! ## Rewards
+//!
+//! The `reward_*` columns are grouped into [`MissionRewards`], see [`MissionsRow::rewards`].
+
+use std::{
+    collections::{BTreeSet, HashSet},
+    fmt,
+    hash::BuildHasher,
+    str::FromStr,
+};
+
+use serde::Serialize;
+
+use super::expr::{self, ExprParseError, Grammar, Parser};
+use crate::{rows::MissionsRow, TypedDatabase};
+
+/// A parsed `Missions.prereqMissionID` expression
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PrereqExpr {
+    /// A single mission
+    Mission {
+        /// The ID of the mission
+        id: i32,
+        /// The state the mission needs to be in, if not "complete"
+        state: Option<i32>,
+    },
+    /// All of the sub-expressions need to be satisfied
+    All(Vec<PrereqExpr>),
+    /// Any of the sub-expressions needs to be satisfied
+    Any(Vec<PrereqExpr>),
+}
+
+/// Error when parsing a [`PrereqExpr`]
+pub type PrereqParseError = ExprParseError;
+
+impl Grammar for PrereqExpr {
+    const AND: &'static [char] = &[',', '&'];
+
+    fn all(list: Vec<Self>) -> Self {
+        Self::All(list)
+    }
+
+    fn any(list: Vec<Self>) -> Self {
+        Self::Any(list)
+    }
+
+    fn atom(id: i32, parser: &mut Parser<'_>) -> Result<Self, ExprParseError> {
+        let state = if let Some((_, ':')) = parser.peek() {
+            parser.bump();
+            Some(parser.number()?)
+        } else {
+            None
+        };
+        Ok(Self::Mission { id, state })
+    }
+}
+
+impl PrereqExpr {
+    /// An expression that is always satisfied
+    pub const NONE: PrereqExpr = PrereqExpr::All(Vec::new());
+
+    /// Parse an expression
+    ///
+    /// An empty string results in [`PrereqExpr::NONE`]
+    pub fn parse(src: &str) -> Result<Self, PrereqParseError> {
+        expr::parse(src)
+    }
+
+    /// Check whether this expression is always satisfied
+    pub fn is_none(&self) -> bool {
+        matches!(self, Self::All(list) if list.is_empty())
+    }
+
+    /// Call `f` for every mission referenced in this expression
+    pub fn visit_missions<F: FnMut(i32, Option<i32>)>(&self, f: &mut F) {
+        match self {
+            Self::Mission { id, state } => f(*id, *state),
+            Self::All(list) | Self::Any(list) => {
+                for e in list {
+                    e.visit_missions(f);
+                }
+            }
+        }
+    }
+
+    /// Return all (distinct) mission IDs referenced in this expression
+    pub fn mission_ids(&self) -> BTreeSet<i32> {
+        let mut ids = BTreeSet::new();
+        self.visit_missions(&mut |id, _| {
+            ids.insert(id);
+        });
+        ids
+    }
+
+    /// Evaluate this expression against the progress of a player
+    pub fn is_satisfied<P: MissionProgress + ?Sized>(&self, progress: &P) -> bool {
+        match self {
+            Self::Mission { id, state: None } => progress.is_completed(*id),
+            Self::Mission {
+                id,
+                state: Some(state),
+            } => progress.has_reached_state(*id, *state),
+            Self::All(list) => list.iter().all(|e| e.is_satisfied(progress)),
+            Self::Any(list) => list.iter().any(|e| e.is_satisfied(progress)),
+        }
+    }
+}
+
+impl FromStr for PrereqExpr {
+    type Err = PrereqParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for PrereqExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn fmt_list(f: &mut fmt::Formatter<'_>, list: &[PrereqExpr], sep: &str) -> fmt::Result {
+            for (i, e) in list.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(sep)?;
+                }
+                match e {
+                    PrereqExpr::Mission { .. } => write!(f, "{}", e)?,
+                    _ => write!(f, "({})", e)?,
+                }
+            }
+            Ok(())
+        }
+
+        match self {
+            Self::Mission { id, state: None } => write!(f, "{}", id),
+            Self::Mission {
+                id,
+                state: Some(state),
+            } => write!(f, "{}:{}", id, state),
+            Self::All(list) => fmt_list(f, list, ","),
+            Self::Any(list) => fmt_list(f, list, "|"),
+        }
+    }
+}
+
+/// The mission progress of a player
+///
+/// This is implemented for sets of completed mission IDs.
+pub trait MissionProgress {
+    /// Check whether the mission has been completed
+    fn is_completed(&self, mission_id: i32) -> bool;
+
+    /// Check whether the mission has reached at least the given state
+    ///
+    /// The default implementation only considers completed missions.
+    fn has_reached_state(&self, mission_id: i32, _state: i32) -> bool {
+        self.is_completed(mission_id)
+    }
+}
+
+impl<S: BuildHasher> MissionProgress for HashSet<i32, S> {
+    fn is_completed(&self, mission_id: i32) -> bool {
+        self.contains(&mission_id)
+    }
+}
+
+impl MissionProgress for BTreeSet<i32> {
+    fn is_completed(&self, mission_id: i32) -> bool {
+        self.contains(&mission_id)
+    }
+}
+
+impl MissionProgress for [i32] {
+    fn is_completed(&self, mission_id: i32) -> bool {
+        self.contains(&mission_id)
+    }
+}
+
+impl<'a, 'b> MissionsRow<'a, 'b> {
+    /// Get the parsed `prereqMissionID` of this mission
+    pub fn prereqs(&self) -> Result<PrereqExpr, PrereqParseError> {
+        match self.prereq_mission_id() {
+            Some(text) => PrereqExpr::parse(&text.decode()),
+            None => Ok(PrereqExpr::NONE),
+        }
+    }
+
+    /// Like [`MissionsRow::prereqs`], but logs errors and treats them as no prerequisites
+    pub(crate) fn prereqs_or_none(&self) -> PrereqExpr {
+        self.prereqs().unwrap_or_else(|e| {
+            log::warn!("Invalid prereqMissionID for mission {}: {}", self.id(), e);
+            PrereqExpr::NONE
+        })
+    }
+}
+
+impl<'db> TypedDatabase<'db> {
+    /// Get the parsed prerequisites of a mission
+    ///
+    /// Returns `None` if the mission does not exist.
+    pub fn get_mission_prereqs(&self, id: i32) -> Option<Result<PrereqExpr, PrereqParseError>> {
+        self.missions.key_iter(id).next().map(|row| row.prereqs())
+    }
+
+    /// Check whether a mission is available to a player
+    ///
+    /// A mission is available if its prerequisites are satisfied and it has either
+    /// not been completed yet or is repeatable. Missing missions are not available.
+    pub fn is_mission_available<P: MissionProgress + ?Sized>(
+        &self,
+        id: i32,
+        progress: &P,
+    ) -> Result<bool, PrereqParseError> {
+        let row = match self.missions.key_iter(id).next() {
+            Some(row) => row,
+            None => return Ok(false),
+        };
+        if progress.is_completed(id) && !row.repeatable() {
+            return Ok(false);
+        }
+        Ok(row.prereqs()?.is_satisfied(progress))
+    }
+}
+
+/// A number of items of one LOT
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub struct ItemReward {
+    /// The LOT of the item
+    pub lot: i32,
+    /// The number of items
+    pub count: i32,
+}
+
+impl ItemReward {
+    /// Create an entry, skipping the `-1`/`0` sentinels for the LOT and count
+    fn new(lot: i32, count: i32) -> Option<Self> {
+        if lot > 0 && count > 0 {
+            Some(Self { lot, count })
+        } else {
+            None
+        }
+    }
+}
+
+/// Increases to the maximum stats of a player
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatIncreases {
+    /// `reward_maximagination`
+    pub max_imagination: i32,
+    /// `reward_maxhealth`
+    pub max_health: i32,
+    /// `reward_maxinventory`
+    pub max_inventory: i32,
+    /// `reward_maxmodel`
+    pub max_model: i32,
+    /// `reward_maxwidget`
+    pub max_widget: i32,
+    /// `reward_maxwallet`
+    pub max_wallet: i64,
+    /// `reward_bankinventory`
+    pub bank_inventory: i32,
+}
+
+impl StatIncreases {
+    /// Check whether none of the stats are increased
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The rewards of a mission
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissionRewards {
+    /// Coins on first completion
+    pub currency: i64,
+    /// Coins on repeated completion
+    pub repeat_currency: i64,
+    /// Reputation
+    pub reputation: i64,
+    /// U-Score (`LegoScore`)
+    pub uscore: i32,
+    /// Whether the player picks one of the items instead of getting all of them
+    pub is_choice: bool,
+    /// Items on first completion
+    pub items: Vec<ItemReward>,
+    /// Items on repeated completion
+    pub repeat_items: Vec<ItemReward>,
+    /// Emotes that are unlocked
+    pub emotes: Vec<i32>,
+    /// Increases to the maximum stats
+    pub stats: StatIncreases,
+}
+
+impl MissionRewards {
+    /// Get the items granted on completion
+    ///
+    /// For choice rewards, only the item at index `choice` is granted, if any.
+    /// Otherwise, `choice` is ignored and all items are granted.
+    pub fn granted_items(&self, repeat: bool, choice: Option<usize>) -> Vec<ItemReward> {
+        let list = if repeat {
+            &self.repeat_items
+        } else {
+            &self.items
+        };
+        if self.is_choice {
+            choice
+                .and_then(|i| list.get(i))
+                .copied()
+                .into_iter()
+                .collect()
+        } else {
+            list.clone()
+        }
+    }
+
+    /// Get the coins granted on completion
+    pub fn granted_currency(&self, repeat: bool) -> i64 {
+        if repeat {
+            self.repeat_currency
+        } else {
+            self.currency
+        }
+    }
+}
+
+impl<'a, 'b> MissionsRow<'a, 'b> {
+    /// Get the structured rewards of this mission
+    pub fn rewards(&self) -> MissionRewards {
+        let items = [
+            (self.reward_item1(), self.reward_item1_count()),
+            (self.reward_item2(), self.reward_item2_count()),
+            (self.reward_item3(), self.reward_item3_count()),
+            (self.reward_item4(), self.reward_item4_count()),
+        ];
+        let repeat_items = [
+            (
+                self.reward_item1_repeatable(),
+                self.reward_item1_repeat_count(),
+            ),
+            (
+                self.reward_item2_repeatable(),
+                self.reward_item2_repeat_count(),
+            ),
+            (
+                self.reward_item3_repeatable(),
+                self.reward_item3_repeat_count(),
+            ),
+            (
+                self.reward_item4_repeatable(),
+                self.reward_item4_repeat_count(),
+            ),
+        ];
+        let emotes = [
+            Some(self.reward_emote()),
+            Some(self.reward_emote2()),
+            self.reward_emote3(),
+            self.reward_emote4(),
+        ];
+        MissionRewards {
+            currency: self.reward_currency(),
+            repeat_currency: self.reward_currency_repeatable().unwrap_or(0),
+            reputation: self.reward_reputation().unwrap_or(0),
+            uscore: self.lego_score(),
+            is_choice: self.is_choice_reward(),
+            items: items
+                .iter()
+                .filter_map(|&(lot, count)| ItemReward::new(lot, count))
+                .collect(),
+            repeat_items: repeat_items
+                .iter()
+                .filter_map(|&(lot, count)| ItemReward::new(lot, count))
+                .collect(),
+            emotes: emotes
+                .iter()
+                .flatten()
+                .copied()
+                .filter(|&e| e > 0)
+                .collect(),
+            stats: StatIncreases {
+                max_imagination: self.reward_maximagination(),
+                max_health: self.reward_maxhealth(),
+                max_inventory: self.reward_maxinventory(),
+                max_model: self.reward_maxmodel().unwrap_or(0),
+                max_widget: self.reward_maxwidget().unwrap_or(0),
+                max_wallet: self.reward_maxwallet().unwrap_or(0),
+                bank_inventory: self.reward_bankinventory().unwrap_or(0),
+            },
+        }
+    }
+}
+
+impl<'db> TypedDatabase<'db> {
+    /// Get the structured rewards of a mission
+    pub fn get_mission_rewards(&self, id: i32) -> Option<MissionRewards> {
+        self.missions.key_iter(id).next().map(|row| row.rewards())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ItemReward, PrereqExpr, PrereqParseError};
+    use crate::ext::test_util::ids;
+
+    fn m(id: i32) -> PrereqExpr {
+        PrereqExpr::Mission { id, state: None }
+    }
+
+    #[test]
+    fn test_precedence() {
+        let expected = PrereqExpr::Any(vec![m(1), PrereqExpr::All(vec![m(2), m(3)])]);
+        assert_eq!(PrereqExpr::parse("1|2,3"), Ok(expected.clone()));
+        assert_eq!(PrereqExpr::parse("1|2&3"), Ok(expected));
+    }
+
+    #[test]
+    fn test_parentheses() {
+        let expected = PrereqExpr::All(vec![PrereqExpr::Any(vec![m(1), m(2)]), m(3)]);
+        assert_eq!(PrereqExpr::parse("(1|2),3"), Ok(expected));
+        assert_eq!(PrereqExpr::parse("((4))"), Ok(m(4)));
+    }
+
+    #[test]
+    fn test_state() {
+        let expected = PrereqExpr::All(vec![
+            PrereqExpr::Mission {
+                id: 1,
+                state: Some(2),
+            },
+            m(3),
+        ]);
+        assert_eq!(PrereqExpr::parse("1:2,3"), Ok(expected));
+    }
+
+    #[test]
+    fn test_whitespace() {
+        let expected = PrereqExpr::Any(vec![m(1), PrereqExpr::All(vec![m(2), m(3)])]);
+        assert_eq!(PrereqExpr::parse(" 1 | ( 2 & 3 ) "), Ok(expected));
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(PrereqExpr::parse(""), Ok(PrereqExpr::NONE));
+        assert!(PrereqExpr::parse("  ").unwrap().is_none());
+        assert!(!m(1).is_none());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            PrereqExpr::parse("1,x"),
+            Err(PrereqParseError::UnexpectedChar('x', 2))
+        );
+        assert_eq!(
+            PrereqExpr::parse("1 2"),
+            Err(PrereqParseError::UnexpectedChar('2', 2))
+        );
+        assert_eq!(
+            PrereqExpr::parse("1:a"),
+            Err(PrereqParseError::UnexpectedChar('a', 2))
+        );
+        assert_eq!(
+            PrereqExpr::parse("1|"),
+            Err(PrereqParseError::UnexpectedEnd)
+        );
+        assert_eq!(
+            PrereqExpr::parse("(1"),
+            Err(PrereqParseError::UnexpectedEnd)
+        );
+        assert_eq!(
+            PrereqExpr::parse("1:"),
+            Err(PrereqParseError::UnexpectedEnd)
+        );
+        assert_eq!(
+            PrereqExpr::parse("1,99999999999"),
+            Err(PrereqParseError::InvalidNumber(2))
+        );
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for src in ["1", "1:2", "1|2,3", "(1|2),3", "1,(2|3:4),(5|(6,7))"] {
+            let expr = PrereqExpr::parse(src).unwrap();
+            let text = expr.to_string();
+            assert_eq!(PrereqExpr::parse(&text), Ok(expr), "{} -> {}", src, text);
+        }
+        assert_eq!(PrereqExpr::parse("(1|2),3").unwrap().to_string(), "(1|2),3");
+    }
+
+    #[test]
+    fn test_is_satisfied() {
+        let expr = PrereqExpr::parse("1|2,3").unwrap();
+        let done = ids(&[2]);
+        assert!(!expr.is_satisfied(&done));
+        let done = ids(&[2, 3]);
+        assert!(expr.is_satisfied(&done));
+        let done = ids(&[1]);
+        assert!(expr.is_satisfied(&done));
+        assert!(PrereqExpr::NONE.is_satisfied(&ids(&[])));
+        assert_eq!(expr.mission_ids(), ids(&[1, 2, 3]));
+    }
+
+    #[test]
+    fn test_item_reward_sentinels() {
+        assert_eq!(
+            ItemReward::new(100, 2),
+            Some(ItemReward { lot: 100, count: 2 })
+        );
+        assert_eq!(ItemReward::new(-1, 2), None);
+        assert_eq!(ItemReward::new(0, 2), None);
+        assert_eq!(ItemReward::new(100, 0), None);
+        assert_eq!(ItemReward::new(100, -1), None);
+    }
+}
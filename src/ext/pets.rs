@@ -0,0 +1,170 @@
+//! # Pets
+//!
+//! A tamable pet has a `PetComponent` and a row in `TamingBuildPuzzles` with
+//! its `NPCLot`. `PetAbilities` is shared by all pets, and pet nests
+//! (`PetNestComponent`) spawn pets of one element type.
+
+use serde::Serialize;
+
+use super::{buffs::Buff, parse_id_list};
+use crate::TypedDatabase;
+
+/// An entry in `PetAbilities`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PetAbility {
+    /// The ID of the ability
+    pub id: i32,
+    /// The name of the ability
+    pub name: String,
+    /// The imagination used by the ability
+    pub imagination_cost: i32,
+}
+
+/// The taming minigame of a pet, from `TamingBuildPuzzles`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TamingPuzzle {
+    /// The ID of the puzzle
+    pub id: i32,
+    /// The LOT of the puzzle model
+    pub puzzle_model_lot: i32,
+    /// The name of the model
+    pub model_name: String,
+    /// The LXF file with the valid pieces
+    pub valid_pieces_lxf: String,
+    /// The LXF file with the invalid pieces
+    pub invalid_pieces_lxf: String,
+    /// The LXF file with the complete model
+    pub full_model_lxf: String,
+    /// The difficulty of the puzzle
+    pub difficulty: i32,
+    /// The time limit in seconds
+    pub time_limit: i32,
+    /// The number of valid pieces
+    pub num_valid_pieces: i32,
+    /// The total number of pieces
+    pub total_num_pieces: i32,
+    /// `Duration`
+    pub duration: f32,
+    /// The imagination used per attempt
+    pub imagination_cost_per_build: i32,
+}
+
+/// The data of a pet
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PetInfo {
+    /// The LOT of the pet
+    pub lot: i32,
+    /// The ID of the `PetComponent`
+    pub component_id: i32,
+    /// The chance to tame the pet, in percent
+    pub percent_tame_chance: f32,
+    /// `tamability`
+    pub tamability: f32,
+    /// The element type of the pet
+    pub element_type: i32,
+    /// `walkSpeed`
+    pub walk_speed: f32,
+    /// `runSpeed`
+    pub run_speed: f32,
+    /// `sprintSpeed`
+    pub sprint_speed: f32,
+    /// `idleTimeMin`
+    pub idle_time_min: f32,
+    /// `idleTimeMax`
+    pub idle_time_max: f32,
+    /// `petForm`
+    pub pet_form: i32,
+    /// The imagination drained while the pet is out
+    pub imagination_drain_rate: f32,
+    /// The IDs from `buffIDs`
+    pub buff_ids: Vec<i32>,
+    /// The buffs from `buffIDs` that exist in `BuffDefinitions`
+    pub buffs: Vec<Buff>,
+    /// The taming puzzle, if the pet can be tamed
+    pub taming_puzzle: Option<TamingPuzzle>,
+    /// The `PetNestComponent` IDs with the same element type
+    pub nest_component_ids: Vec<i32>,
+    /// The abilities available to all pets
+    pub abilities: Vec<PetAbility>,
+}
+
+impl<'db> TypedDatabase<'db> {
+    /// Get all entries from `PetAbilities`
+    pub fn get_pet_abilities(&self) -> Vec<PetAbility> {
+        match &self.pet_abilities {
+            Some(table) => table
+                .row_iter()
+                .map(|row| PetAbility {
+                    id: row.id(),
+                    name: row.ability_name().decode().into_owned(),
+                    imagination_cost: row.imagination_cost(),
+                })
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Get the taming puzzle for a pet LOT
+    pub fn get_taming_puzzle(&self, lot: i32) -> Option<TamingPuzzle> {
+        let row = self
+            .taming_build_puzzles
+            .row_iter()
+            .find(|row| row.npc_lot() == lot)?;
+        Some(TamingPuzzle {
+            id: row.id(),
+            puzzle_model_lot: row.puzzle_model_lot(),
+            model_name: row.model_name().decode().into_owned(),
+            valid_pieces_lxf: row.valid_pieces_lxf().decode().into_owned(),
+            invalid_pieces_lxf: row.invalid_pieces_lxf().decode().into_owned(),
+            full_model_lxf: row.full_model_lxf().decode().into_owned(),
+            difficulty: row.difficulty(),
+            time_limit: row.timelimit(),
+            num_valid_pieces: row.num_valid_pieces(),
+            total_num_pieces: row.total_num_pieces(),
+            duration: row.duration(),
+            imagination_cost_per_build: row.imag_cost_per_build(),
+        })
+    }
+
+    /// Get the combined data of a pet
+    pub fn get_pet(&self, lot: i32) -> Option<PetInfo> {
+        let component_id = self.get_components(lot).pet?;
+        let row = self.pet_component.as_ref()?.key_iter(component_id).next()?;
+        let buff_ids = row.buff_i_ds().map(parse_id_list).unwrap_or_default();
+        let buffs = buff_ids
+            .iter()
+            .filter_map(|&id| self.get_buff(id))
+            .collect();
+        let element_type = row.element_type();
+        let nest_component_ids = match &self.pet_nest_component {
+            Some(table) => table
+                .row_iter()
+                .filter(|nest| nest.elemental_type() == element_type)
+                .map(|nest| nest.id())
+                .collect(),
+            None => Vec::new(),
+        };
+        Some(PetInfo {
+            lot,
+            component_id,
+            percent_tame_chance: row.percent_tame_chance(),
+            tamability: row.tamability(),
+            element_type,
+            walk_speed: row.walk_speed(),
+            run_speed: row.run_speed(),
+            sprint_speed: row.sprint_speed(),
+            idle_time_min: row.idle_time_min(),
+            idle_time_max: row.idle_time_max(),
+            pet_form: row.pet_form(),
+            imagination_drain_rate: row.imagination_drain_rate(),
+            buff_ids,
+            buffs,
+            taming_puzzle: self.get_taming_puzzle(lot),
+            nest_component_ids,
+            abilities: self.get_pet_abilities(),
+        })
+    }
+}
@@ -0,0 +1,517 @@
+//! # Preconditions
+//!
+//! Columns like `ItemComponent.reqPrecondition` contain expressions over IDs from the
+//! `Preconditions` table:
+//!
+//! - `|` means *any of*
+//! - `,`, `;` and `&` all mean *all of* and bind tighter than `|`
+//! - parentheses group sub-expressions
+//!
+//! An empty (or missing) expression is always satisfied. The entries of the
+//! `Preconditions` table are checked against a [`PlayerState`].
+
+use std::{collections::BTreeSet, str::FromStr};
+
+use latin1str::Latin1Str;
+use serde::Serialize;
+
+use super::{
+    expr::{self, ExprParseError, Grammar, Parser},
+    missions::MissionProgress,
+    parse_id_list,
+};
+use crate::{
+    rows::{ItemComponentRow, RailActivatorComponentRow, RocketLaunchpadControlComponentRow},
+    tables::PreconditionsTable,
+    TypedDatabase,
+};
+
+/// The type of a precondition (`Preconditions.type`)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize)]
+pub enum PreconditionType {
+    /// One of the items is equipped
+    ItemEquipped,
+    /// None of the items are equipped
+    ItemNotEquipped,
+    /// The player has one of the items
+    HasItem,
+    /// The player has none of the items
+    DoesNotHaveItem,
+    /// One of the achievements is complete
+    HasAchievement,
+    /// One of the missions is available
+    MissionAvailable,
+    /// The player is on one of the missions
+    OnMission,
+    /// One of the missions is complete
+    MissionComplete,
+    /// A pet is deployed
+    PetDeployed,
+    /// One of the flags is set
+    HasFlag,
+    /// The player is within a shape
+    WithinShape,
+    /// The player is in build mode
+    InBuild,
+    /// A team check
+    TeamCheck,
+    /// The player is taming a pet
+    IsPetTaming,
+    /// The player has one of the factions
+    HasFaction,
+    /// The player has none of the factions
+    DoesNotHaveFaction,
+    /// The player has a racing licence
+    HasRacingLicence,
+    /// The player has no racing licence
+    DoesNotHaveRacingLicence,
+    /// The player is a member of the LEGO club
+    LegoClubMember,
+    /// The player is not interacting with anything
+    NoInteraction,
+    /// The player has at least the given level
+    HasLevel,
+    /// A precondition type not known to this crate
+    Unknown(i32),
+}
+
+impl From<i32> for PreconditionType {
+    fn from(value: i32) -> Self {
+        match value {
+            0 => Self::ItemEquipped,
+            1 => Self::ItemNotEquipped,
+            2 => Self::HasItem,
+            3 => Self::DoesNotHaveItem,
+            4 => Self::HasAchievement,
+            5 => Self::MissionAvailable,
+            6 => Self::OnMission,
+            7 => Self::MissionComplete,
+            8 => Self::PetDeployed,
+            9 => Self::HasFlag,
+            10 => Self::WithinShape,
+            11 => Self::InBuild,
+            12 => Self::TeamCheck,
+            13 => Self::IsPetTaming,
+            14 => Self::HasFaction,
+            15 => Self::DoesNotHaveFaction,
+            16 => Self::HasRacingLicence,
+            17 => Self::DoesNotHaveRacingLicence,
+            18 => Self::LegoClubMember,
+            19 => Self::NoInteraction,
+            22 => Self::HasLevel,
+            _ => Self::Unknown(value),
+        }
+    }
+}
+
+impl PreconditionType {
+    /// Whether the precondition fails as soon as one value fails
+    ///
+    /// All other types pass as soon as one value passes.
+    fn requires_all(&self) -> bool {
+        matches!(
+            self,
+            Self::ItemNotEquipped | Self::DoesNotHaveItem | Self::HasLevel
+        )
+    }
+}
+
+/// The state of a player that preconditions are checked against
+pub trait PlayerState {
+    /// The number of items with the given LOT in the inventory
+    fn item_count(&self, lot: i32) -> i32;
+    /// Whether an item with the given LOT is equipped
+    fn is_equipped(&self, lot: i32) -> bool;
+    /// Whether the mission (or achievement) has been completed
+    fn is_mission_complete(&self, mission_id: i32) -> bool;
+    /// Whether the mission is currently active
+    fn is_mission_active(&self, mission_id: i32) -> bool;
+    /// Whether the player flag is set
+    fn has_flag(&self, flag_id: i32) -> bool;
+    /// The level of the player
+    fn level(&self) -> i32;
+
+    /// Check a precondition type that is not covered by the methods above
+    ///
+    /// The default implementation passes every check.
+    fn check_other(&self, _kind: PreconditionType, _value: i32) -> bool {
+        true
+    }
+}
+
+/// Adapter to check mission prerequisites with a [`PlayerState`]
+struct CompletedMissions<'a, P: ?Sized>(&'a P);
+
+impl<'a, P: PlayerState + ?Sized> MissionProgress for CompletedMissions<'a, P> {
+    fn is_completed(&self, mission_id: i32) -> bool {
+        self.0.is_mission_complete(mission_id)
+    }
+}
+
+/// An entry in the `Preconditions` table
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Precondition<'a> {
+    /// The ID of the precondition
+    pub id: i32,
+    /// The type of the precondition
+    pub kind: PreconditionType,
+    /// The values to check, from `targetLOT`
+    pub targets: Vec<i32>,
+    /// `targetGroup`
+    pub target_group: Option<&'a Latin1Str>,
+    /// `targetCount`
+    pub target_count: Option<i32>,
+    /// The icon of the precondition
+    pub icon_id: Option<i32>,
+    /// `validContexts`
+    pub valid_contexts: i64,
+}
+
+impl<'a> Precondition<'a> {
+    /// Check this precondition
+    ///
+    /// `db` is used to resolve [`PreconditionType::MissionAvailable`].
+    pub fn check<P: PlayerState + ?Sized>(&self, db: &TypedDatabase, player: &P) -> bool {
+        self.check_with(player, |mission_id| {
+            db.is_mission_available(mission_id, &CompletedMissions(player))
+                .unwrap_or(false)
+        })
+    }
+
+    /// Check this precondition, with `mission_available` for the mission prerequisites
+    fn check_with<P, F>(&self, player: &P, mission_available: F) -> bool
+    where
+        P: PlayerState + ?Sized,
+        F: Fn(i32) -> bool,
+    {
+        if self.targets.is_empty() {
+            return true;
+        }
+        let all = self.kind.requires_all();
+        for &value in &self.targets {
+            let passed = self.check_value(player, &mission_available, value);
+            if passed != all {
+                return passed;
+            }
+        }
+        all
+    }
+
+    fn check_value<P, F>(&self, player: &P, mission_available: &F, value: i32) -> bool
+    where
+        P: PlayerState + ?Sized,
+        F: Fn(i32) -> bool,
+    {
+        let count = self.target_count.unwrap_or(1).max(1);
+        match self.kind {
+            PreconditionType::ItemEquipped => player.is_equipped(value),
+            PreconditionType::ItemNotEquipped => !player.is_equipped(value),
+            PreconditionType::HasItem => player.item_count(value) >= count,
+            PreconditionType::DoesNotHaveItem => player.item_count(value) < count,
+            PreconditionType::HasAchievement | PreconditionType::MissionComplete => {
+                player.is_mission_complete(value)
+            }
+            PreconditionType::MissionAvailable => {
+                !player.is_mission_active(value) && mission_available(value)
+            }
+            PreconditionType::OnMission => player.is_mission_active(value),
+            PreconditionType::HasFlag => player.has_flag(value),
+            PreconditionType::HasLevel => player.level() >= value,
+            kind => player.check_other(kind, value),
+        }
+    }
+}
+
+impl<'db> PreconditionsTable<'db> {
+    /// Get the data for a precondition
+    pub fn get_data(&self, id: i32) -> Option<Precondition<'_>> {
+        self.key_iter(id).next().map(|row| Precondition {
+            id,
+            kind: PreconditionType::from(row.r#type().unwrap_or(-1)),
+            targets: row.target_lot().map(parse_id_list).unwrap_or_default(),
+            target_group: row.target_group(),
+            target_count: row.target_count(),
+            icon_id: row.icon_id(),
+            valid_contexts: row.valid_contexts(),
+        })
+    }
+}
+
+/// A parsed precondition expression
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PreconditionExpr {
+    /// A single entry of the `Preconditions` table
+    Precondition(i32),
+    /// All of the sub-expressions need to be satisfied
+    All(Vec<PreconditionExpr>),
+    /// Any of the sub-expressions needs to be satisfied
+    Any(Vec<PreconditionExpr>),
+}
+
+/// Error when parsing a [`PreconditionExpr`]
+pub type PreconditionParseError = ExprParseError;
+
+impl Grammar for PreconditionExpr {
+    const AND: &'static [char] = &[',', ';', '&'];
+
+    fn all(list: Vec<Self>) -> Self {
+        Self::All(list)
+    }
+
+    fn any(list: Vec<Self>) -> Self {
+        Self::Any(list)
+    }
+
+    fn atom(id: i32, _parser: &mut Parser<'_>) -> Result<Self, ExprParseError> {
+        Ok(Self::Precondition(id))
+    }
+}
+
+impl PreconditionExpr {
+    /// An expression that is always satisfied
+    pub const NONE: PreconditionExpr = PreconditionExpr::All(Vec::new());
+
+    /// Parse an expression
+    ///
+    /// An empty string results in [`PreconditionExpr::NONE`]
+    pub fn parse(src: &str) -> Result<Self, PreconditionParseError> {
+        expr::parse(src)
+    }
+
+    fn parse_opt(text: Option<&Latin1Str>) -> Result<Self, PreconditionParseError> {
+        match text {
+            Some(text) => Self::parse(&text.decode()),
+            None => Ok(Self::NONE),
+        }
+    }
+
+    /// Check whether this expression is always satisfied
+    pub fn is_none(&self) -> bool {
+        matches!(self, Self::All(list) if list.is_empty())
+    }
+
+    /// Return all precondition IDs referenced in this expression
+    pub fn precondition_ids(&self) -> BTreeSet<i32> {
+        let mut ids = BTreeSet::new();
+        self.collect_ids(&mut ids);
+        ids
+    }
+
+    fn collect_ids(&self, ids: &mut BTreeSet<i32>) {
+        match self {
+            Self::Precondition(id) => {
+                ids.insert(*id);
+            }
+            Self::All(list) | Self::Any(list) => {
+                for e in list {
+                    e.collect_ids(ids);
+                }
+            }
+        }
+    }
+
+    /// Evaluate this expression for a player
+    ///
+    /// IDs that are missing from the `Preconditions` table are considered satisfied.
+    pub fn evaluate<P: PlayerState + ?Sized>(&self, db: &TypedDatabase, player: &P) -> bool {
+        match self {
+            Self::Precondition(id) => match db.preconditions.get_data(*id) {
+                Some(precondition) => precondition.check(db, player),
+                None => {
+                    log::warn!("Missing precondition {}", id);
+                    true
+                }
+            },
+            Self::All(list) => list.iter().all(|e| e.evaluate(db, player)),
+            Self::Any(list) => list.iter().any(|e| e.evaluate(db, player)),
+        }
+    }
+}
+
+impl FromStr for PreconditionExpr {
+    type Err = PreconditionParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl<'a, 'b> ItemComponentRow<'a, 'b> {
+    /// Get the parsed `reqPrecondition` of this item
+    pub fn req_precondition_expr(&self) -> Result<PreconditionExpr, PreconditionParseError> {
+        PreconditionExpr::parse_opt(self.req_precondition())
+    }
+}
+
+impl<'a, 'b> RocketLaunchpadControlComponentRow<'a, 'b> {
+    /// Get the parsed `launchPrecondition`, if `useLaunchPrecondition` is set
+    pub fn launch_precondition_expr(&self) -> Result<PreconditionExpr, PreconditionParseError> {
+        if self.use_launch_precondition() {
+            PreconditionExpr::parse_opt(self.launch_precondition())
+        } else {
+            Ok(PreconditionExpr::NONE)
+        }
+    }
+
+    /// Get the parsed `altLandingPrecondition`, if `useAltLandingPrecondition` is set
+    pub fn alt_landing_precondition_expr(
+        &self,
+    ) -> Result<PreconditionExpr, PreconditionParseError> {
+        if self.use_alt_landing_precondition() {
+            PreconditionExpr::parse_opt(self.alt_landing_precondition())
+        } else {
+            Ok(PreconditionExpr::NONE)
+        }
+    }
+}
+
+impl<'a, 'b> RailActivatorComponentRow<'a, 'b> {
+    /// Get the parsed `preconditions` of this rail
+    pub fn preconditions_expr(&self) -> Result<PreconditionExpr, PreconditionParseError> {
+        PreconditionExpr::parse_opt(self.preconditions())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Precondition, PreconditionExpr, PreconditionType};
+    use crate::ext::test_util::{ids, StubPlayer};
+
+    const BASE: Precondition<'static> = Precondition {
+        id: 1,
+        kind: PreconditionType::Unknown(-1),
+        targets: Vec::new(),
+        target_group: None,
+        target_count: None,
+        icon_id: None,
+        valid_contexts: 0,
+    };
+
+    fn check(p: &Precondition, player: &StubPlayer) -> bool {
+        p.check_with(player, |_| true)
+    }
+
+    #[test]
+    fn test_has_item_any() {
+        let p = Precondition {
+            kind: PreconditionType::HasItem,
+            targets: vec![10, 20],
+            ..BASE
+        };
+        assert!(!check(&p, &StubPlayer::default()));
+        assert!(check(&p, &StubPlayer::with_items(&[(20, 1)])));
+        assert!(check(&p, &StubPlayer::with_items(&[(10, 1), (20, 1)])));
+    }
+
+    #[test]
+    fn test_has_item_count() {
+        let p = Precondition {
+            kind: PreconditionType::HasItem,
+            targets: vec![10, 20],
+            target_count: Some(3),
+            ..BASE
+        };
+        assert!(!check(&p, &StubPlayer::with_items(&[(10, 2), (20, 2)])));
+        assert!(check(&p, &StubPlayer::with_items(&[(10, 2), (20, 3)])));
+    }
+
+    #[test]
+    fn test_does_not_have_item_all() {
+        let p = Precondition {
+            kind: PreconditionType::DoesNotHaveItem,
+            targets: vec![10, 20],
+            ..BASE
+        };
+        assert!(check(&p, &StubPlayer::default()));
+        assert!(!check(&p, &StubPlayer::with_items(&[(20, 1)])));
+
+        let p = Precondition {
+            target_count: Some(2),
+            ..p
+        };
+        assert!(check(&p, &StubPlayer::with_items(&[(10, 1), (20, 1)])));
+        assert!(!check(&p, &StubPlayer::with_items(&[(10, 2)])));
+    }
+
+    #[test]
+    fn test_has_level_all() {
+        let p = Precondition {
+            kind: PreconditionType::HasLevel,
+            targets: vec![5, 10],
+            ..BASE
+        };
+        for &(level, passed) in &[(4, false), (7, false), (10, true)] {
+            let player = StubPlayer {
+                level,
+                ..Default::default()
+            };
+            assert_eq!(check(&p, &player), passed);
+        }
+    }
+
+    #[test]
+    fn test_missions_and_flags() {
+        let player = StubPlayer {
+            completed: ids(&[1]),
+            active: ids(&[2]),
+            flags: ids(&[3]),
+            ..Default::default()
+        };
+        let complete = Precondition {
+            kind: PreconditionType::MissionComplete,
+            targets: vec![1],
+            ..BASE
+        };
+        assert!(check(&complete, &player));
+        let on_mission = Precondition {
+            kind: PreconditionType::OnMission,
+            targets: vec![1],
+            ..BASE
+        };
+        assert!(!check(&on_mission, &player));
+        let available = Precondition {
+            kind: PreconditionType::MissionAvailable,
+            targets: vec![2],
+            ..BASE
+        };
+        // Active missions are not available again
+        assert!(!check(&available, &player));
+        let flag = Precondition {
+            kind: PreconditionType::HasFlag,
+            targets: vec![4, 3],
+            ..BASE
+        };
+        assert!(check(&flag, &player));
+    }
+
+    #[test]
+    fn test_no_targets() {
+        let p = Precondition {
+            kind: PreconditionType::HasItem,
+            ..BASE
+        };
+        assert!(check(&p, &StubPlayer::default()));
+    }
+
+    #[test]
+    fn test_parse_semicolon() {
+        use PreconditionExpr::{All, Any, Precondition as P};
+        assert_eq!(
+            PreconditionExpr::parse("1;2|3"),
+            Ok(Any(vec![All(vec![P(1), P(2)]), P(3)]))
+        );
+        assert_eq!(
+            PreconditionExpr::parse("1; 2,3&4"),
+            Ok(All(vec![P(1), P(2), P(3), P(4)]))
+        );
+        assert_eq!(PreconditionExpr::parse(""), Ok(PreconditionExpr::NONE));
+        assert_eq!(
+            PreconditionExpr::parse("1;2|(2,1)")
+                .unwrap()
+                .precondition_ids(),
+            ids(&[1, 2])
+        );
+    }
+}
@@ -0,0 +1,311 @@
+//! # Level and reputation progression
+//!
+//! `LevelProgressionLookup` lists the total U-Score required for every level.
+//! Players can't go past `WorldConfig.LevelCap`; U-Score earned after that is
+//! converted to coins at a rate of `WorldConfig.LevelCapCurrencyConversion`.
+//!
+//! `ReputationRewards` lists the reputation required for every reputation level
+//! and sublevel.
+
+use std::{convert::TryFrom, error::Error, fmt};
+
+use serde::Serialize;
+
+use crate::TypedDatabase;
+
+/// A problem with the progression data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgressionError {
+    /// `LevelProgressionLookup` is missing or empty
+    NoLevels,
+    /// The required U-Score of a level is lower than that of the previous level
+    NotIncreasing(i32),
+    /// There is no `LevelProgressionLookup` entry for `WorldConfig.LevelCap`
+    LevelCapMissing(i32),
+    /// `WorldConfig.LevelCapCurrencyConversion` is negative
+    NegativeConversion(i32),
+}
+
+impl fmt::Display for ProgressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoLevels => write!(f, "no levels in LevelProgressionLookup"),
+            Self::NotIncreasing(level) => {
+                write!(f, "required U-Score of level {} is decreasing", level)
+            }
+            Self::LevelCapMissing(cap) => {
+                write!(f, "level cap {} is not in LevelProgressionLookup", cap)
+            }
+            Self::NegativeConversion(rate) => {
+                write!(f, "negative LevelCapCurrencyConversion {}", rate)
+            }
+        }
+    }
+}
+
+impl Error for ProgressionError {}
+
+/// The level progression of players
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelProgression {
+    /// `(level, requiredUScore)`, ordered by level
+    pub levels: Vec<(i32, u64)>,
+    /// The maximum level (`WorldConfig.LevelCap`)
+    pub level_cap: i32,
+    /// The coins per U-Score earned at the level cap
+    pub level_cap_currency_conversion: i32,
+}
+
+impl LevelProgression {
+    /// Load the progression from the database
+    ///
+    /// If `WorldConfig` is missing, the highest level in the lookup is the cap
+    /// and no coins are granted for U-Score at the cap.
+    pub fn new(db: &TypedDatabase) -> Self {
+        let mut levels: Vec<(i32, u64)> = match &db.level_progression_lookup {
+            Some(table) => table
+                .row_iter()
+                .map(|row| (row.id(), row.required_u_score().max(0) as u64))
+                .collect(),
+            None => Vec::new(),
+        };
+        levels.sort_unstable();
+        let world_config = db.load_world_config().ok();
+        let level_cap = world_config
+            .as_ref()
+            .map(|config| config.level_cap)
+            .or_else(|| levels.last().map(|&(level, _)| level))
+            .unwrap_or(0);
+        Self {
+            levels,
+            level_cap,
+            level_cap_currency_conversion: world_config
+                .map(|config| config.economy.level_cap_currency_conversion)
+                .unwrap_or(0),
+        }
+    }
+
+    /// Check the lookup against `WorldConfig`
+    pub fn validate(&self) -> Result<(), ProgressionError> {
+        if self.levels.is_empty() {
+            return Err(ProgressionError::NoLevels);
+        }
+        for pair in self.levels.windows(2) {
+            if pair[1].1 < pair[0].1 {
+                return Err(ProgressionError::NotIncreasing(pair[1].0));
+            }
+        }
+        if self.uscore_for_level(self.level_cap).is_none() {
+            return Err(ProgressionError::LevelCapMissing(self.level_cap));
+        }
+        if self.level_cap_currency_conversion < 0 {
+            return Err(ProgressionError::NegativeConversion(
+                self.level_cap_currency_conversion,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Get the total U-Score required for a level
+    pub fn uscore_for_level(&self, level: i32) -> Option<u64> {
+        self.levels
+            .binary_search_by_key(&level, |&(l, _)| l)
+            .ok()
+            .map(|i| self.levels[i].1)
+    }
+
+    /// Get the level for a total U-Score, limited by the level cap
+    pub fn level_for_uscore(&self, uscore: u64) -> i32 {
+        self.levels
+            .iter()
+            .take_while(|&&(level, _)| level <= self.level_cap)
+            .filter(|&&(_, required)| required <= uscore)
+            .map(|&(level, _)| level)
+            .last()
+            .unwrap_or(1)
+    }
+
+    /// Get the U-Score needed to get from `level` to the next level
+    ///
+    /// Returns `None` at the level cap or for unknown levels.
+    pub fn uscore_to_next_level(&self, level: i32) -> Option<u64> {
+        if level >= self.level_cap {
+            return None;
+        }
+        let current = self.uscore_for_level(level)?;
+        let next = self.uscore_for_level(level + 1)?;
+        Some(next.saturating_sub(current))
+    }
+
+    /// Whether a total U-Score reaches the level cap
+    pub fn is_at_cap(&self, uscore: u64) -> bool {
+        self.level_for_uscore(uscore) >= self.level_cap
+    }
+
+    /// Get the coins granted instead of U-Score at the level cap
+    pub fn uscore_to_currency(&self, uscore: u64) -> i64 {
+        i64::try_from(uscore)
+            .unwrap_or(i64::MAX)
+            .saturating_mul(i64::from(self.level_cap_currency_conversion))
+    }
+}
+
+/// An entry in `ReputationRewards`
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReputationLevel {
+    /// The reputation level
+    pub level: i32,
+    /// The sublevel within the level
+    pub sublevel: i32,
+    /// The reputation required for this sublevel
+    pub reputation: f32,
+}
+
+/// All entries in `ReputationRewards`, ordered by required reputation
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(transparent)]
+pub struct ReputationLevels {
+    levels: Vec<ReputationLevel>,
+}
+
+impl ReputationLevels {
+    /// Create the lookup from a list of levels in any order
+    pub fn new(mut levels: Vec<ReputationLevel>) -> Self {
+        levels.sort_by(|a, b| a.reputation.total_cmp(&b.reputation));
+        Self { levels }
+    }
+
+    /// Get all levels, ordered by required reputation
+    pub fn as_slice(&self) -> &[ReputationLevel] {
+        &self.levels
+    }
+
+    /// Get the highest reputation level reached with some reputation
+    pub fn level(&self, reputation: f32) -> Option<ReputationLevel> {
+        let reached = self
+            .levels
+            .partition_point(|level| level.reputation <= reputation);
+        reached.checked_sub(1).map(|i| self.levels[i])
+    }
+}
+
+impl<'db> TypedDatabase<'db> {
+    /// Load the [`LevelProgression`]
+    pub fn level_progression(&self) -> LevelProgression {
+        LevelProgression::new(self)
+    }
+
+    /// Load all reputation levels
+    pub fn reputation_levels(&self) -> ReputationLevels {
+        let levels = match &self.reputation_rewards {
+            Some(table) => table
+                .row_iter()
+                .map(|row| ReputationLevel {
+                    level: row.rep_level(),
+                    sublevel: row.sublevel(),
+                    reputation: row.reputation(),
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        ReputationLevels::new(levels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LevelProgression, ProgressionError, ReputationLevel, ReputationLevels};
+
+    fn progression() -> LevelProgression {
+        LevelProgression {
+            levels: vec![(1, 0), (2, 100), (3, 300), (4, 600)],
+            level_cap: 3,
+            level_cap_currency_conversion: 2,
+        }
+    }
+
+    #[test]
+    fn test_level_for_uscore() {
+        let p = progression();
+        assert_eq!(p.level_for_uscore(0), 1);
+        assert_eq!(p.level_for_uscore(99), 1);
+        assert_eq!(p.level_for_uscore(100), 2);
+        assert_eq!(p.level_for_uscore(300), 3);
+        // Level 4 is above the cap
+        assert_eq!(p.level_for_uscore(1000), 3);
+        assert!(!p.is_at_cap(299));
+        assert!(p.is_at_cap(300));
+
+        let empty = LevelProgression {
+            levels: Vec::new(),
+            level_cap: 0,
+            level_cap_currency_conversion: 0,
+        };
+        assert_eq!(empty.level_for_uscore(1000), 1);
+    }
+
+    #[test]
+    fn test_uscore_to_next_level() {
+        let p = progression();
+        assert_eq!(p.uscore_to_next_level(1), Some(100));
+        assert_eq!(p.uscore_to_next_level(2), Some(200));
+        assert_eq!(p.uscore_to_next_level(3), None);
+        assert_eq!(p.uscore_to_next_level(0), None);
+    }
+
+    #[test]
+    fn test_uscore_to_currency() {
+        let p = progression();
+        assert_eq!(p.uscore_to_currency(0), 0);
+        assert_eq!(p.uscore_to_currency(50), 100);
+        assert_eq!(p.uscore_to_currency(u64::MAX), i64::MAX);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(progression().validate(), Ok(()));
+        let p = LevelProgression {
+            levels: Vec::new(),
+            ..progression()
+        };
+        assert_eq!(p.validate(), Err(ProgressionError::NoLevels));
+        let p = LevelProgression {
+            levels: vec![(1, 0), (2, 100), (3, 50)],
+            ..progression()
+        };
+        assert_eq!(p.validate(), Err(ProgressionError::NotIncreasing(3)));
+        let p = LevelProgression {
+            level_cap: 5,
+            ..progression()
+        };
+        assert_eq!(p.validate(), Err(ProgressionError::LevelCapMissing(5)));
+        let p = LevelProgression {
+            level_cap_currency_conversion: -1,
+            ..progression()
+        };
+        assert_eq!(p.validate(), Err(ProgressionError::NegativeConversion(-1)));
+    }
+
+    #[test]
+    fn test_reputation_level() {
+        let levels = ReputationLevels::new(vec![
+            ReputationLevel {
+                level: 1,
+                sublevel: 2,
+                reputation: 50.0,
+            },
+            ReputationLevel {
+                level: 1,
+                sublevel: 1,
+                reputation: 10.0,
+            },
+        ]);
+        assert_eq!(levels.as_slice()[0].sublevel, 1);
+        assert_eq!(levels.level(9.0), None);
+        assert_eq!(levels.level(10.0).map(|l| l.sublevel), Some(1));
+        assert_eq!(levels.level(1000.0).map(|l| l.sublevel), Some(2));
+        assert_eq!(ReputationLevels::default().level(10.0), None);
+    }
+}
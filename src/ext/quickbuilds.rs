@@ -0,0 +1,159 @@
+//! # Quickbuilds
+//!
+//! A quickbuild has a `RebuildComponent` with its timings and costs, and is
+//! assembled from the `RebuildSections` with a matching `rebuildID`. Choice
+//! builds additionally have a `ChoiceBuildComponent` that lists the models the
+//! player can pick from.
+
+use std::time::Duration;
+
+use serde::Serialize;
+
+use super::{activities::Activity, parse_id_list};
+use crate::TypedDatabase;
+
+/// Convert a number of seconds to a [`Duration`], treating invalid values as zero
+fn secs(value: f32) -> Duration {
+    // `NaN` fails both comparisons
+    if value > 0.0 && value < u64::MAX as f32 {
+        Duration::from_secs_f32(value)
+    } else {
+        Duration::ZERO
+    }
+}
+
+/// An entry in `RebuildSections`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RebuildSection {
+    /// The ID of the section
+    pub id: i32,
+    /// The LOT of the object
+    pub object_lot: i32,
+    /// The position relative to the quickbuild
+    pub offset: [f32; 3],
+    /// The rotation while falling into place
+    pub fall_angle: Option<[f32; 3]>,
+    /// The height the section falls from
+    pub fall_height: Option<f32>,
+    /// The sections that need to be placed first
+    pub requires: Vec<i32>,
+    /// `size`
+    pub size: i32,
+    /// Whether the section starts out placed
+    pub placed: bool,
+}
+
+/// The data of a `ChoiceBuildComponent`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChoiceBuild {
+    /// The ID of the component
+    pub id: i32,
+    /// The LOTs the player can choose from
+    pub selections: Vec<i32>,
+    /// The imagination cost that replaces the one of the quickbuild
+    pub imagination_override: Option<i32>,
+}
+
+/// The data of a quickbuild object
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Quickbuild {
+    /// The LOT of the object
+    pub lot: i32,
+    /// The ID of the `RebuildComponent`
+    pub component_id: i32,
+    /// The time until an unfinished build resets
+    pub reset_time: Duration,
+    /// The time it takes to build
+    pub complete_time: Option<Duration>,
+    /// The time until a finished build is smashed
+    pub time_before_smash: Duration,
+    /// The imagination used while building
+    pub take_imagination: i32,
+    /// The imagination used after building
+    pub post_imagination_cost: Option<i32>,
+    /// Whether the build can be interrupted
+    pub interruptible: bool,
+    /// Whether the build activates itself
+    pub self_activator: bool,
+    /// The LOTs from `custom_modules`
+    pub custom_modules: Vec<i32>,
+    /// The linked activity
+    pub activity_id: Option<i32>,
+    /// The sections of the build, ordered by ID
+    pub sections: Vec<RebuildSection>,
+    /// The choice build data, if the object has a `ChoiceBuildComponent`
+    pub choice_build: Option<ChoiceBuild>,
+}
+
+impl<'db> TypedDatabase<'db> {
+    /// Get the sections of a `RebuildComponent`
+    pub fn get_rebuild_sections(&self, rebuild_id: i32) -> Vec<RebuildSection> {
+        let table = match &self.rebuild_sections {
+            Some(table) => table,
+            None => return Vec::new(),
+        };
+        let mut sections: Vec<RebuildSection> = table
+            .row_iter()
+            .filter(|row| row.rebuild_id() == rebuild_id)
+            .map(|row| {
+                let fall_angle = match (row.fall_angle_x(), row.fall_angle_y(), row.fall_angle_z())
+                {
+                    (Some(x), Some(y), Some(z)) => Some([x, y, z]),
+                    _ => None,
+                };
+                RebuildSection {
+                    id: row.id(),
+                    object_lot: row.object_id(),
+                    offset: [row.offset_x(), row.offset_y(), row.offset_z()],
+                    fall_angle,
+                    fall_height: row.fall_height(),
+                    requires: row.requires_list().map(parse_id_list).unwrap_or_default(),
+                    size: row.size(),
+                    placed: row.b_placed(),
+                }
+            })
+            .collect();
+        sections.sort_by_key(|section| section.id);
+        sections
+    }
+
+    /// Get the choice build data of an object
+    pub fn get_choice_build(&self, lot: i32) -> Option<ChoiceBuild> {
+        let id = self.get_components(lot).choice_build?;
+        let row = self.choice_build_component.as_ref()?.key_iter(id).next()?;
+        Some(ChoiceBuild {
+            id,
+            selections: parse_id_list(row.selections()),
+            imagination_override: row.imagination_override(),
+        })
+    }
+
+    /// Get the quickbuild data of an object
+    pub fn get_quickbuild(&self, lot: i32) -> Option<Quickbuild> {
+        let component_id = self.get_components(lot).quickbuild?;
+        let row = self.rebuild_component.key_iter(component_id).next()?;
+        Some(Quickbuild {
+            lot,
+            component_id,
+            reset_time: secs(row.reset_time()),
+            complete_time: row.complete_time().map(secs),
+            time_before_smash: secs(row.time_before_smash()),
+            take_imagination: row.take_imagination(),
+            post_imagination_cost: row.post_imagination_cost(),
+            interruptible: row.interruptible(),
+            self_activator: row.self_activator(),
+            custom_modules: row.custom_modules().map(parse_id_list).unwrap_or_default(),
+            activity_id: row.activity_id(),
+            sections: self.get_rebuild_sections(component_id),
+            choice_build: self.get_choice_build(lot),
+        })
+    }
+
+    /// Get the activity linked to a quickbuild
+    pub fn get_quickbuild_activity(&self, quickbuild: &Quickbuild) -> Option<Activity> {
+        self.get_activity(quickbuild.activity_id?)
+    }
+}
@@ -0,0 +1,104 @@
+//! # Skill loadouts
+//!
+//! Objects get skills from `ObjectSkills`. Wearing enough items of an item set
+//! additionally grants the skills of the `ItemSetSkills` group for that tier.
+
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+
+use super::{parse_id_list, SkillBehavior};
+use crate::TypedDatabase;
+
+/// Where a [`GrantedSkill`] comes from
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SkillSource {
+    /// The skill is granted by an item
+    Item {
+        /// The LOT of the item
+        lot: i32,
+    },
+    /// The skill is granted by a completed item set tier
+    #[serde(rename_all = "camelCase")]
+    ItemSet {
+        /// The ID of the item set
+        set_id: i32,
+        /// The number of equipped items required for this tier
+        items: usize,
+    },
+}
+
+/// A skill granted by the equipped items
+#[derive(Debug, Copy, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrantedSkill {
+    /// The ID of the skill
+    pub skill_id: i32,
+    /// Where the skill comes from
+    pub source: SkillSource,
+    /// `ObjectSkills.castOnType` or `ItemSetSkills.SkillCastType`
+    pub cast_on_type: Option<i32>,
+    /// The `SkillBehavior` entry of the skill
+    pub behavior: Option<SkillBehavior>,
+}
+
+impl<'db> TypedDatabase<'db> {
+    /// Get the skills of an object template from `ObjectSkills`
+    pub fn get_object_skills(&self, lot: i32) -> Vec<GrantedSkill> {
+        self.object_skills
+            .key_iter(lot)
+            .map(|row| GrantedSkill {
+                skill_id: row.skill_id(),
+                source: SkillSource::Item { lot },
+                cast_on_type: row.cast_on_type(),
+                behavior: self.skills.get_data(row.skill_id()),
+            })
+            .collect()
+    }
+
+    /// Get the skills of an `ItemSetSkills` group
+    pub fn get_item_set_skills(&self, skill_set_id: i32, source: SkillSource) -> Vec<GrantedSkill> {
+        self.item_set_skills
+            .key_iter(skill_set_id)
+            .map(|row| GrantedSkill {
+                skill_id: row.skill_id(),
+                source,
+                cast_on_type: Some(row.skill_cast_type()),
+                behavior: self.skills.get_data(row.skill_id()),
+            })
+            .collect()
+    }
+
+    /// Get all skills granted by a set of equipped items
+    ///
+    /// This includes the skills of the items themselves and of every item set tier
+    /// for which enough items are equipped.
+    pub fn skill_loadout(&self, equipped: &[i32]) -> Vec<GrantedSkill> {
+        let equipped: BTreeSet<i32> = equipped.iter().copied().collect();
+        let mut skills: Vec<GrantedSkill> = equipped
+            .iter()
+            .flat_map(|&lot| self.get_object_skills(lot))
+            .collect();
+
+        for row in self.item_sets.row_iter() {
+            let count = parse_id_list(row.item_i_ds())
+                .into_iter()
+                .filter(|lot| equipped.contains(lot))
+                .count();
+            if count < 2 {
+                continue;
+            }
+            let set_id = row.set_id();
+            if let Some(set) = self.item_sets.get_data(set_id) {
+                for &(items, skill_set_id) in &set.skill_sets {
+                    if items <= count {
+                        let source = SkillSource::ItemSet { set_id, items };
+                        skills.extend(self.get_item_set_skills(skill_set_id, source));
+                    }
+                }
+            }
+        }
+        skills
+    }
+}
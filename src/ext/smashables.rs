@@ -0,0 +1,364 @@
+//! # Smashable chains
+//!
+//! `SmashableChainIndex` lists the chains, and `SmashableChain` has one row per
+//! level of a chain. Each level takes a number of smashes, which have to
+//! happen within its time limit, and grants loot and coins when completed.
+//!
+//! [`ChainProgress`] tracks the progress of a single player.
+
+use std::{collections::BTreeMap, time::Duration};
+
+use serde::Serialize;
+
+use super::{loot::LootMatrixEntry, CurrencyRange};
+use crate::TypedDatabase;
+
+/// A level of a smashable chain
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmashableChainStep {
+    /// `chainLevel`
+    pub level: i32,
+    /// `chainStepID`
+    pub step_id: i32,
+    /// The number of smashes needed to complete the step
+    pub smash_count: i32,
+    /// The maximum time between two smashes, if any
+    pub time_limit: Option<Duration>,
+    /// The loot matrix granted on completion
+    pub loot_matrix_id: i32,
+    /// `rarityTableIndex`
+    pub rarity_table_index: i32,
+    /// The entries of the loot matrix
+    pub loot: Vec<LootMatrixEntry>,
+    /// `currencyIndex`
+    pub currency_index: i32,
+    /// `currencyLevel`
+    pub currency_level: i32,
+    /// The coins granted on completion
+    pub currency: Option<CurrencyRange>,
+}
+
+/// A smashable chain with all its steps
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmashableChain {
+    /// The ID of the chain
+    pub index: i32,
+    /// The group of objects that count towards the chain
+    pub target_group: String,
+    /// The description of the chain
+    pub description: String,
+    /// Whether the last step repeats after the chain is completed
+    pub continuous: bool,
+    /// The steps, ordered by level
+    pub steps: Vec<SmashableChainStep>,
+}
+
+/// What happened when advancing a [`ChainProgress`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChainEvent {
+    /// The smash was counted towards the current step
+    #[serde(rename_all = "camelCase")]
+    Progress {
+        /// The index of the current step
+        step: usize,
+        /// The smashes in the current step so far
+        smashes: i32,
+    },
+    /// The step at this index was completed and its rewards are granted
+    StepCompleted(usize),
+    /// The last step was completed and the chain is over
+    ChainCompleted(usize),
+    /// The chain is over or has no steps, nothing happened
+    Inactive,
+}
+
+/// The progress of a player in a [`SmashableChain`]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainProgress {
+    /// The index of the current step
+    pub step: usize,
+    /// The smashes in the current step
+    pub smashes: i32,
+    /// The time of the last smash
+    pub last_smash: Option<Duration>,
+    /// Whether the chain is over, see [`ChainProgress::smash`]
+    pub finished: bool,
+}
+
+impl ChainProgress {
+    /// Start a new chain
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Go back to the first step
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Whether the time limit of the current step ran out at `now`
+    pub fn is_expired(&self, chain: &SmashableChain, now: Duration) -> bool {
+        let limit = chain.steps.get(self.step).and_then(|step| step.time_limit);
+        match (self.last_smash, limit) {
+            (Some(last), Some(limit)) => now.saturating_sub(last) > limit,
+            _ => false,
+        }
+    }
+
+    /// Count a smash at time `now`
+    ///
+    /// `now` may be measured from any fixed point in time. If the time limit of
+    /// the current step ran out, the chain starts over before counting the smash.
+    /// A step with a `smash_count` below 1 is completed by a single smash.
+    ///
+    /// Once a chain that is not `continuous` is completed, it stays finished and
+    /// every smash returns [`ChainEvent::Inactive`] until [`ChainProgress::reset`]
+    /// is called.
+    pub fn smash(&mut self, chain: &SmashableChain, now: Duration) -> ChainEvent {
+        if self.finished {
+            return ChainEvent::Inactive;
+        }
+        if self.is_expired(chain, now) {
+            self.reset();
+        }
+        let step = match chain.steps.get(self.step) {
+            Some(step) => step,
+            None => return ChainEvent::Inactive,
+        };
+        self.last_smash = Some(now);
+        self.smashes += 1;
+        if self.smashes < step.smash_count {
+            return ChainEvent::Progress {
+                step: self.step,
+                smashes: self.smashes,
+            };
+        }
+
+        let completed = self.step;
+        self.smashes = 0;
+        if completed + 1 < chain.steps.len() {
+            self.step += 1;
+            ChainEvent::StepCompleted(completed)
+        } else if chain.continuous {
+            ChainEvent::StepCompleted(completed)
+        } else {
+            self.finished = true;
+            ChainEvent::ChainCompleted(completed)
+        }
+    }
+}
+
+impl<'db> TypedDatabase<'db> {
+    /// Get a smashable chain with its steps, loot and coins
+    pub fn get_smashable_chain(&self, index: i32) -> Option<SmashableChain> {
+        let row = self
+            .smashable_chain_index
+            .as_ref()?
+            .key_iter(index)
+            .next()?;
+        let mut steps: Vec<SmashableChainStep> = match &self.smashable_chain {
+            Some(table) => table
+                .key_iter(index)
+                .map(|step| SmashableChainStep {
+                    level: step.chain_level(),
+                    step_id: step.chain_step_id(),
+                    smash_count: step.smash_count(),
+                    time_limit: Some(step.time_limit())
+                        .filter(|&limit| limit > 0)
+                        .map(|limit| Duration::from_secs(limit as u64)),
+                    loot_matrix_id: step.loot_matrix_id(),
+                    rarity_table_index: step.rarity_table_index(),
+                    loot: self.get_loot_matrix(step.loot_matrix_id()),
+                    currency_index: step.currency_index(),
+                    currency_level: step.currency_level(),
+                    currency: self.currency_range(step.currency_index(), step.currency_level()),
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        steps.sort_by_key(|step| step.level);
+        Some(SmashableChain {
+            index,
+            target_group: row.target_group().decode().into_owned(),
+            description: row.description().decode().into_owned(),
+            continuous: row.continuous() != 0,
+            steps,
+        })
+    }
+
+    /// Get the drop weights from `SmashableElements`, by element ID
+    pub fn get_smashable_elements(&self) -> BTreeMap<i32, i32> {
+        match &self.smashable_elements {
+            Some(table) => table
+                .row_iter()
+                .map(|row| (row.element_id(), row.drop_weight()))
+                .collect(),
+            None => BTreeMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{ChainEvent, ChainProgress, SmashableChain, SmashableChainStep};
+
+    const NOW: Duration = Duration::from_secs(0);
+
+    #[test]
+    fn test_progress_and_step_completion() {
+        let chain = SmashableChain {
+            steps: vec![
+                SmashableChainStep {
+                    smash_count: 2,
+                    ..Default::default()
+                },
+                SmashableChainStep {
+                    smash_count: 1,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let mut progress = ChainProgress::new();
+        assert_eq!(
+            progress.smash(&chain, NOW),
+            ChainEvent::Progress {
+                step: 0,
+                smashes: 1
+            }
+        );
+        assert_eq!(progress.smash(&chain, NOW), ChainEvent::StepCompleted(0));
+        assert_eq!(progress.step, 1);
+        assert_eq!(progress.smashes, 0);
+        assert_eq!(progress.smash(&chain, NOW), ChainEvent::ChainCompleted(1));
+    }
+
+    #[test]
+    fn test_expiry_resets() {
+        let chain = SmashableChain {
+            steps: vec![
+                SmashableChainStep {
+                    smash_count: 1,
+                    ..Default::default()
+                },
+                SmashableChainStep {
+                    smash_count: 3,
+                    time_limit: Some(Duration::from_secs(5)),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let mut progress = ChainProgress::new();
+        assert_eq!(progress.smash(&chain, NOW), ChainEvent::StepCompleted(0));
+        assert_eq!(
+            progress.smash(&chain, Duration::from_secs(4)),
+            ChainEvent::Progress {
+                step: 1,
+                smashes: 1
+            }
+        );
+        assert!(!progress.is_expired(&chain, Duration::from_secs(9)));
+        assert!(progress.is_expired(&chain, Duration::from_secs(10)));
+        // The chain starts over and the smash counts for the first step
+        assert_eq!(
+            progress.smash(&chain, Duration::from_secs(10)),
+            ChainEvent::StepCompleted(0)
+        );
+        assert_eq!(progress.step, 1);
+    }
+
+    #[test]
+    fn test_non_continuous_stays_finished() {
+        let chain = SmashableChain {
+            steps: vec![SmashableChainStep {
+                smash_count: 1,
+                time_limit: Some(Duration::from_secs(5)),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let mut progress = ChainProgress::new();
+        assert_eq!(progress.smash(&chain, NOW), ChainEvent::ChainCompleted(0));
+        assert!(progress.finished);
+        assert_eq!(
+            progress.smash(&chain, Duration::from_secs(1)),
+            ChainEvent::Inactive
+        );
+        // Running out of time does not restart a finished chain
+        assert_eq!(
+            progress.smash(&chain, Duration::from_secs(100)),
+            ChainEvent::Inactive
+        );
+
+        progress.reset();
+        assert_eq!(
+            progress.smash(&chain, Duration::from_secs(101)),
+            ChainEvent::ChainCompleted(0)
+        );
+    }
+
+    #[test]
+    fn test_continuous_repeats_last_step() {
+        let chain = SmashableChain {
+            continuous: true,
+            steps: vec![
+                SmashableChainStep {
+                    smash_count: 1,
+                    ..Default::default()
+                },
+                SmashableChainStep {
+                    smash_count: 2,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let mut progress = ChainProgress::new();
+        assert_eq!(progress.smash(&chain, NOW), ChainEvent::StepCompleted(0));
+        for _ in 0..3 {
+            assert_eq!(
+                progress.smash(&chain, NOW),
+                ChainEvent::Progress {
+                    step: 1,
+                    smashes: 1
+                }
+            );
+            assert_eq!(progress.smash(&chain, NOW), ChainEvent::StepCompleted(1));
+        }
+        assert!(!progress.finished);
+    }
+
+    #[test]
+    fn test_zero_smash_count() {
+        let chain = SmashableChain {
+            steps: vec![
+                SmashableChainStep {
+                    smash_count: 0,
+                    ..Default::default()
+                },
+                SmashableChainStep {
+                    smash_count: -1,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let mut progress = ChainProgress::new();
+        assert_eq!(progress.smash(&chain, NOW), ChainEvent::StepCompleted(0));
+        assert_eq!(progress.smash(&chain, NOW), ChainEvent::ChainCompleted(1));
+    }
+
+    #[test]
+    fn test_empty_chain() {
+        let chain = SmashableChain::default();
+        let mut progress = ChainProgress::new();
+        assert_eq!(progress.smash(&chain, NOW), ChainEvent::Inactive);
+    }
+}
@@ -0,0 +1,140 @@
+//! # Speedchat
+//!
+//! `SpeedchatMenu` stores the quick chat menu as a list of nodes, each with an
+//! optional `parentId`. Leaf nodes usually play an entry of `Emotes`.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::TypedDatabase;
+
+/// An entry in the `Emotes` table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Emote {
+    /// The ID of the emote
+    pub id: i32,
+    /// The animation to play
+    pub animation_name: Option<String>,
+    /// The icon of the emote
+    pub icon_filename: String,
+    /// The chat channel
+    pub channel: Option<String>,
+    /// The chat command that plays the emote
+    pub command: Option<String>,
+    /// Whether the emote needs to be unlocked first
+    pub locked: bool,
+}
+
+/// A node in the speedchat menu
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeedchatNode {
+    /// The ID of the menu entry
+    pub id: i32,
+    /// The image of the entry
+    pub image_name: Option<String>,
+    /// The emote played by the entry
+    pub emote: Option<Emote>,
+    /// The sub-entries, ordered by ID
+    pub children: Vec<SpeedchatNode>,
+}
+
+impl SpeedchatNode {
+    /// Find a node in this subtree
+    pub fn find(&self, id: i32) -> Option<&SpeedchatNode> {
+        if self.id == id {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(id))
+    }
+}
+
+struct MenuEntry {
+    parent_id: Option<i32>,
+    emote_id: Option<i32>,
+    image_name: Option<String>,
+}
+
+impl<'db> TypedDatabase<'db> {
+    /// Get an entry from `Emotes`
+    pub fn get_emote(&self, id: i32) -> Option<Emote> {
+        let row = self.emotes.key_iter(id).next()?;
+        Some(Emote {
+            id,
+            animation_name: row.animation_name().map(|s| s.decode().into_owned()),
+            icon_filename: row.icon_filename().decode().into_owned(),
+            channel: row.channel().map(|s| s.decode().into_owned()),
+            command: row.command().map(|s| s.decode().into_owned()),
+            locked: row.locked(),
+        })
+    }
+
+    /// Build the speedchat menu tree
+    ///
+    /// Entries without a parent, or whose parent doesn't exist, are returned as
+    /// roots. Entries that are part of a cycle are skipped.
+    pub fn speedchat_menu(&self) -> Vec<SpeedchatNode> {
+        let entries: BTreeMap<i32, MenuEntry> = self
+            .speedchat_menu
+            .row_iter()
+            .map(|row| {
+                let entry = MenuEntry {
+                    parent_id: row.parent_id(),
+                    emote_id: row.emote_id(),
+                    image_name: row.image_name().map(|s| s.decode().into_owned()),
+                };
+                (row.id(), entry)
+            })
+            .collect();
+
+        let mut children: BTreeMap<Option<i32>, Vec<i32>> = BTreeMap::new();
+        for (&id, entry) in &entries {
+            let parent = entry.parent_id.filter(|p| entries.contains_key(p));
+            children.entry(parent).or_default().push(id);
+        }
+
+        let mut visited = 0;
+        let roots = self.speedchat_children(&entries, &children, None, &mut visited);
+        if visited < entries.len() {
+            log::warn!(
+                "Skipped {} speedchat entries in a cycle",
+                entries.len() - visited
+            );
+        }
+        roots
+    }
+
+    fn speedchat_children(
+        &self,
+        entries: &BTreeMap<i32, MenuEntry>,
+        children: &BTreeMap<Option<i32>, Vec<i32>>,
+        parent: Option<i32>,
+        visited: &mut usize,
+    ) -> Vec<SpeedchatNode> {
+        let ids = match children.get(&parent) {
+            Some(ids) => ids,
+            None => return Vec::new(),
+        };
+        ids.iter()
+            .map(|&id| {
+                *visited += 1;
+                let entry = &entries[&id];
+                let emote = entry.emote_id.and_then(|emote_id| {
+                    let emote = self.get_emote(emote_id);
+                    if emote.is_none() {
+                        log::warn!("Missing emote {} for speedchat entry {}", emote_id, id);
+                    }
+                    emote
+                });
+                SpeedchatNode {
+                    id,
+                    image_name: entry.image_name.clone(),
+                    emote,
+                    children: self.speedchat_children(entries, children, Some(id), visited),
+                }
+            })
+            .collect()
+    }
+}
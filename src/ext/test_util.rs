@@ -0,0 +1,79 @@
+//! Shared helpers for the unit tests of the `ext` modules
+//!
+//! Test data is built with struct literals (using `..Default::default()` where
+//! the type allows it), the player side is covered by [`StubPlayer`].
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use latin1str::Latin1Str;
+
+use super::{missions::MissionProgress, preconditions::PlayerState};
+
+/// Collect a list of IDs into a set
+pub fn ids(list: &[i32]) -> BTreeSet<i32> {
+    list.iter().copied().collect()
+}
+
+/// View an ASCII string as a [`Latin1Str`]
+pub fn latin1(text: &str) -> &Latin1Str {
+    Latin1Str::from_bytes_until_nul(text.as_bytes())
+}
+
+/// A player with a fixed state
+#[derive(Debug, Default)]
+pub struct StubPlayer {
+    /// Item counts by LOT
+    pub items: BTreeMap<i32, i32>,
+    /// The LOTs of the equipped items
+    pub equipped: BTreeSet<i32>,
+    /// The completed missions
+    pub completed: BTreeSet<i32>,
+    /// The active missions
+    pub active: BTreeSet<i32>,
+    /// The flags that are set
+    pub flags: BTreeSet<i32>,
+    /// The level of the player
+    pub level: i32,
+}
+
+impl StubPlayer {
+    /// A player holding the given `(lot, count)` items
+    pub fn with_items(items: &[(i32, i32)]) -> Self {
+        Self {
+            items: items.iter().copied().collect(),
+            ..Default::default()
+        }
+    }
+}
+
+impl PlayerState for StubPlayer {
+    fn item_count(&self, lot: i32) -> i32 {
+        self.items.get(&lot).copied().unwrap_or(0)
+    }
+
+    fn is_equipped(&self, lot: i32) -> bool {
+        self.equipped.contains(&lot)
+    }
+
+    fn is_mission_complete(&self, mission_id: i32) -> bool {
+        self.completed.contains(&mission_id)
+    }
+
+    fn is_mission_active(&self, mission_id: i32) -> bool {
+        self.active.contains(&mission_id)
+    }
+
+    fn has_flag(&self, flag_id: i32) -> bool {
+        self.flags.contains(&flag_id)
+    }
+
+    fn level(&self) -> i32 {
+        self.level
+    }
+}
+
+impl MissionProgress for StubPlayer {
+    fn is_completed(&self, mission_id: i32) -> bool {
+        self.completed.contains(&mission_id)
+    }
+}
@@ -0,0 +1,75 @@
+//! # In-database texts
+//!
+//! `TextLanguage` has the text for every `TextID` and `LanguageID`, the
+//! languages are listed in `LanguageType`, and `TextDescription` has a
+//! description of every text, which is used when no translation exists.
+
+use latin1str::Latin1Str;
+use serde::Serialize;
+
+use crate::TypedDatabase;
+
+/// An entry in `LanguageType`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Language {
+    /// The ID of the language
+    pub id: i32,
+    /// The description of the language
+    pub description: String,
+}
+
+impl<'db> TypedDatabase<'db> {
+    /// Get all languages, ordered by ID
+    pub fn languages(&self) -> Vec<Language> {
+        let mut languages: Vec<Language> = match &self.language_type {
+            Some(table) => table
+                .row_iter()
+                .map(|row| Language {
+                    id: row.language_id(),
+                    description: row.language_description().decode().into_owned(),
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        languages.sort_by_key(|lang| lang.id);
+        languages
+    }
+
+    /// Get the languages a text is available in
+    pub fn text_languages(&self, id: i32) -> Vec<i32> {
+        match &self.text_language {
+            Some(table) => table.key_iter(id).map(|row| row.language_id()).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Get the text of a `TextID` in one language, without fallback
+    pub fn text_exact(&self, id: i32, lang: i32) -> Option<&Latin1Str> {
+        self.text_language
+            .as_ref()?
+            .key_iter(id)
+            .find(|row| row.language_id() == lang)
+            .map(|row| row.text())
+    }
+
+    /// Get the text of a `TextID`
+    ///
+    /// If there is no text in `lang`, the text in the first language of
+    /// `LanguageType` is used, and then the `TextDescription`.
+    pub fn text(&self, id: i32, lang: i32) -> Option<&Latin1Str> {
+        self.text_exact(id, lang)
+            .or_else(|| {
+                let table = self.language_type.as_ref()?;
+                let default = table.row_iter().map(|row| row.language_id()).min()?;
+                self.text_exact(id, default)
+            })
+            .or_else(|| {
+                self.text_description
+                    .as_ref()?
+                    .key_iter(id)
+                    .next()
+                    .map(|row| row.test_description())
+            })
+    }
+}
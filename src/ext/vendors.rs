@@ -0,0 +1,130 @@
+//! # Vendors
+//!
+//! A `VendorComponent` sells the items of its loot matrix. The prices are computed
+//! from the `ItemComponent` of every item:
+//!
+//! - buy price: `baseValue * buyScalar * WorldConfig.vendor_buy_multiplier`
+//! - sell price: `baseValue * sellScalar * SellMultiplier`
+//!
+//! Both are rounded down. Missing multipliers count as `1.0`.
+
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+
+use crate::TypedDatabase;
+
+/// A number of items of one LOT that is paid in addition to coins
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub struct ItemCost {
+    /// The LOT of the currency item
+    pub lot: i32,
+    /// The number of items
+    pub count: i32,
+}
+
+impl ItemCost {
+    fn new(lot: Option<i32>, count: Option<i32>) -> Option<Self> {
+        match (lot, count) {
+            (Some(lot), Some(count)) if lot > 0 && count > 0 => Some(Self { lot, count }),
+            _ => None,
+        }
+    }
+}
+
+/// An item sold by a vendor
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VendorItem {
+    /// The LOT of the item
+    pub lot: i32,
+    /// The sort order of the item
+    pub sort_priority: i32,
+    /// The coins to pay when buying the item
+    pub buy_price: i64,
+    /// The coins received when selling the item
+    pub sell_price: i64,
+    /// The alternate currency to pay (`currencyLOT` and `altCurrencyCost`)
+    pub alt_currency: Option<ItemCost>,
+    /// The commendation tokens to pay (`commendationLOT` and `commendationCost`)
+    pub commendation: Option<ItemCost>,
+}
+
+/// The data of a vendor component
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Vendor {
+    /// The ID of the vendor component
+    pub id: i32,
+    /// The factor applied to buy prices
+    pub buy_scalar: f32,
+    /// The factor applied to sell prices
+    pub sell_scalar: f32,
+    /// The time in seconds after which the inventory is refreshed
+    pub refresh_time_seconds: f32,
+    /// The loot matrix that contains the items
+    pub loot_matrix_index: i32,
+    /// The items sold by the vendor, ordered by `sortPriority`
+    pub items: Vec<VendorItem>,
+}
+
+impl<'db> TypedDatabase<'db> {
+    /// Get the `vendor_buy_multiplier` from `WorldConfig`, or `1.0`
+    fn vendor_buy_multiplier(&self) -> f32 {
+        self.world_config
+            .as_ref()
+            .and_then(|table| table.row_iter().next())
+            .map(|row| row.vendor_buy_multiplier())
+            .unwrap_or(1.0)
+    }
+
+    /// Get the vendor of an object, with all items
+    ///
+    /// Items without an item component are skipped.
+    pub fn get_vendor(&self, lot: i32) -> Option<Vendor> {
+        let id = self.get_components(lot).vendor?;
+        let row = self.vendor_component.as_ref()?.key_iter(id).next()?;
+        let buy_scalar = row.buy_scalar();
+        let sell_scalar = row.sell_scalar();
+        let buy_multiplier = self.vendor_buy_multiplier();
+
+        let mut seen = BTreeSet::new();
+        let mut items = Vec::new();
+        for entry in self.get_loot_matrix(row.loot_matrix_index()) {
+            for item in entry.items {
+                if !seen.insert(item.lot) {
+                    continue;
+                }
+                let comp = match self.get_item_component(item.lot) {
+                    Some(comp) => comp,
+                    None => continue,
+                };
+                let base_value = comp.base_value().unwrap_or(0) as f32;
+                let sell_multiplier = comp.sell_multiplier().unwrap_or(1.0);
+                items.push(VendorItem {
+                    lot: item.lot,
+                    sort_priority: item.sort_priority,
+                    buy_price: (base_value * buy_scalar * buy_multiplier).floor() as i64,
+                    sell_price: (base_value * sell_scalar * sell_multiplier).floor() as i64,
+                    alt_currency: ItemCost::new(comp.currency_lot(), comp.alt_currency_cost()),
+                    commendation: ItemCost::new(comp.commendation_lot(), comp.commendation_cost()),
+                });
+            }
+        }
+        items.sort_by_key(|item| item.sort_priority);
+
+        Some(Vendor {
+            id,
+            buy_scalar,
+            sell_scalar,
+            refresh_time_seconds: row.refresh_time_seconds(),
+            loot_matrix_index: row.loot_matrix_index(),
+            items,
+        })
+    }
+
+    /// Get the items sold by the vendor of an object, with prices
+    pub fn vendor_inventory(&self, lot: i32) -> Option<Vec<VendorItem>> {
+        self.get_vendor(lot).map(|vendor| vendor.items)
+    }
+}
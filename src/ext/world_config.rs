@@ -0,0 +1,271 @@
+//! # World configuration
+//!
+//! `WorldConfig` has a single row with global gameplay constants.
+
+use std::{error::Error, fmt};
+
+use serde::Serialize;
+
+use crate::{rows::WorldConfigRow, TypedDatabase};
+
+/// An error when loading the [`WorldConfig`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WorldConfigError {
+    /// The `WorldConfig` table is missing
+    MissingTable,
+    /// The table doesn't have exactly one row
+    RowCount(usize),
+}
+
+impl fmt::Display for WorldConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingTable => write!(f, "missing table WorldConfig"),
+            Self::RowCount(n) => write!(f, "expected 1 row in WorldConfig, found {}", n),
+        }
+    }
+}
+
+impl Error for WorldConfigError {}
+
+/// Character movement constants
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MovementConfig {
+    /// `character_rotation_speed`
+    pub rotation_speed: f32,
+    /// `character_walk_forward_speed`
+    pub walk_forward_speed: f32,
+    /// `character_walk_backward_speed`
+    pub walk_backward_speed: f32,
+    /// `character_walk_strafe_speed`
+    pub walk_strafe_speed: f32,
+    /// `character_walk_strafe_forward_speed`
+    pub walk_strafe_forward_speed: f32,
+    /// `character_walk_strafe_backward_speed`
+    pub walk_strafe_backward_speed: f32,
+    /// `character_run_backward_speed`
+    pub run_backward_speed: f32,
+    /// `character_run_strafe_speed`
+    pub run_strafe_speed: f32,
+    /// `character_run_strafe_forward_speed`
+    pub run_strafe_forward_speed: f32,
+    /// `character_run_strafe_backward_speed`
+    pub run_strafe_backward_speed: f32,
+    /// `characterGroundedTime`
+    pub grounded_time: f32,
+    /// `characterGroundedSpeed`
+    pub grounded_speed: f32,
+    /// `character_max_slope`
+    pub max_slope: f32,
+    /// `character_eye_height`
+    pub eye_height: f32,
+}
+
+/// Flight constants
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightConfig {
+    /// `flight_vertical_velocity`
+    pub vertical_velocity: f32,
+    /// `flight_airspeed`
+    pub airspeed: f32,
+    /// `flight_fuel_ratio`
+    pub fuel_ratio: f32,
+    /// `flight_max_airspeed`
+    pub max_airspeed: f32,
+}
+
+/// Coin and fee constants
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EconomyConfig {
+    /// `vendor_buy_multiplier`
+    pub vendor_buy_multiplier: f32,
+    /// `coins_lost_on_death_percent`
+    pub coins_lost_on_death_percent: f32,
+    /// `coins_lost_on_death_min`
+    pub coins_lost_on_death_min: i32,
+    /// `coins_lost_on_death_max`
+    pub coins_lost_on_death_max: i32,
+    /// `coins_lost_on_death_min_timeout`
+    pub coins_lost_on_death_min_timeout: f32,
+    /// `coins_lost_on_death_max_timeout`
+    pub coins_lost_on_death_max_timeout: f32,
+    /// `mail_base_fee`
+    pub mail_base_fee: i32,
+    /// `mail_percent_attachment_fee`
+    pub mail_percent_attachment_fee: f32,
+    /// `LevelCapCurrencyConversion`
+    pub level_cap_currency_conversion: i32,
+}
+
+/// Property, moderation and voting constants
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertyConfig {
+    /// `nPropertyCloneLimit`
+    pub clone_limit: i32,
+    /// `defaultHomespaceTemplate`
+    pub default_homespace_template: i32,
+    /// `defaultPropertyMaxHeight`
+    pub default_max_height: f32,
+    /// `modelModerateOnCreate`
+    pub model_moderate_on_create: bool,
+    /// `property_moderation_request_approval_cost`
+    pub moderation_request_approval_cost: i32,
+    /// `property_moderation_request_review_cost`
+    pub moderation_request_review_cost: i32,
+    /// `propertyModRequestsAllowedSpike`
+    pub mod_requests_allowed_spike: i32,
+    /// `propertyModRequestsAllowedInterval`
+    pub mod_requests_allowed_interval: i32,
+    /// `propertyModRequestsAllowedTotal`
+    pub mod_requests_allowed_total: i32,
+    /// `propertyModRequestsSpikeDuration`
+    pub mod_requests_spike_duration: i32,
+    /// `propertyModRequestsIntervalDuration`
+    pub mod_requests_interval_duration: i32,
+    /// `character_votes_per_day`
+    pub votes_per_day: i32,
+    /// `fReputationPerVote`
+    pub reputation_per_vote: f32,
+    /// `reputationPerVoteCast`
+    pub reputation_per_vote_cast: f32,
+    /// `reputationPerVoteReceived`
+    pub reputation_per_vote_received: f32,
+    /// `reputationPerBattlePromotion`
+    pub reputation_per_battle_promotion: f32,
+    /// `showcaseTopModelConsiderationBattles`
+    pub showcase_top_model_consideration_battles: i32,
+    /// `propertyReputationDelay`
+    pub reputation_delay: i32,
+}
+
+/// The contents of the `WorldConfig` table
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorldConfig {
+    /// `WorldConfigID`
+    pub id: i32,
+    /// `pegravityvalue`
+    pub gravity: f32,
+    /// `pebroadphaseworldsize`
+    pub broadphase_world_size: f32,
+    /// `pegameobjscalefactor`
+    pub game_obj_scale_factor: f32,
+    /// `global_cooldown`
+    pub global_cooldown: f32,
+    /// `globalImmunityTime`
+    pub global_immunity_time: f32,
+    /// `defaultrespawntime`
+    pub default_respawn_time: f32,
+    /// `mission_tooltip_timeout`
+    pub mission_tooltip_timeout: f32,
+    /// `pet_follow_radius`
+    pub pet_follow_radius: f32,
+    /// `LevelCap`
+    pub level_cap: i32,
+    /// `LevelUpBehaviorEffect`
+    pub level_up_behavior_effect: String,
+    /// `CharacterVersion`
+    pub character_version: i32,
+    /// Character movement
+    pub movement: MovementConfig,
+    /// Flight
+    pub flight: FlightConfig,
+    /// Coins and fees
+    pub economy: EconomyConfig,
+    /// Properties, moderation and voting
+    pub property: PropertyConfig,
+}
+
+impl WorldConfig {
+    /// Read the config from a row
+    pub fn from_row(row: &WorldConfigRow) -> Self {
+        Self {
+            id: row.world_config_id(),
+            gravity: row.pegravityvalue(),
+            broadphase_world_size: row.pebroadphaseworldsize(),
+            game_obj_scale_factor: row.pegameobjscalefactor(),
+            global_cooldown: row.global_cooldown(),
+            global_immunity_time: row.global_immunity_time(),
+            default_respawn_time: row.defaultrespawntime(),
+            mission_tooltip_timeout: row.mission_tooltip_timeout(),
+            pet_follow_radius: row.pet_follow_radius(),
+            level_cap: row.level_cap(),
+            level_up_behavior_effect: row.level_up_behavior_effect().decode().into_owned(),
+            character_version: row.character_version(),
+            movement: MovementConfig {
+                rotation_speed: row.character_rotation_speed(),
+                walk_forward_speed: row.character_walk_forward_speed(),
+                walk_backward_speed: row.character_walk_backward_speed(),
+                walk_strafe_speed: row.character_walk_strafe_speed(),
+                walk_strafe_forward_speed: row.character_walk_strafe_forward_speed(),
+                walk_strafe_backward_speed: row.character_walk_strafe_backward_speed(),
+                run_backward_speed: row.character_run_backward_speed(),
+                run_strafe_speed: row.character_run_strafe_speed(),
+                run_strafe_forward_speed: row.character_run_strafe_forward_speed(),
+                run_strafe_backward_speed: row.character_run_strafe_backward_speed(),
+                grounded_time: row.character_grounded_time(),
+                grounded_speed: row.character_grounded_speed(),
+                max_slope: row.character_max_slope(),
+                eye_height: row.character_eye_height(),
+            },
+            flight: FlightConfig {
+                vertical_velocity: row.flight_vertical_velocity(),
+                airspeed: row.flight_airspeed(),
+                fuel_ratio: row.flight_fuel_ratio(),
+                max_airspeed: row.flight_max_airspeed(),
+            },
+            economy: EconomyConfig {
+                vendor_buy_multiplier: row.vendor_buy_multiplier(),
+                coins_lost_on_death_percent: row.coins_lost_on_death_percent(),
+                coins_lost_on_death_min: row.coins_lost_on_death_min(),
+                coins_lost_on_death_max: row.coins_lost_on_death_max(),
+                coins_lost_on_death_min_timeout: row.coins_lost_on_death_min_timeout(),
+                coins_lost_on_death_max_timeout: row.coins_lost_on_death_max_timeout(),
+                mail_base_fee: row.mail_base_fee(),
+                mail_percent_attachment_fee: row.mail_percent_attachment_fee(),
+                level_cap_currency_conversion: row.level_cap_currency_conversion(),
+            },
+            property: PropertyConfig {
+                clone_limit: row.n_property_clone_limit(),
+                default_homespace_template: row.default_homespace_template(),
+                default_max_height: row.default_property_max_height(),
+                model_moderate_on_create: row.model_moderate_on_create(),
+                moderation_request_approval_cost: row.property_moderation_request_approval_cost(),
+                moderation_request_review_cost: row.property_moderation_request_review_cost(),
+                mod_requests_allowed_spike: row.property_mod_requests_allowed_spike(),
+                mod_requests_allowed_interval: row.property_mod_requests_allowed_interval(),
+                mod_requests_allowed_total: row.property_mod_requests_allowed_total(),
+                mod_requests_spike_duration: row.property_mod_requests_spike_duration(),
+                mod_requests_interval_duration: row.property_mod_requests_interval_duration(),
+                votes_per_day: row.character_votes_per_day(),
+                reputation_per_vote: row.f_reputation_per_vote(),
+                reputation_per_vote_cast: row.reputation_per_vote_cast(),
+                reputation_per_vote_received: row.reputation_per_vote_received(),
+                reputation_per_battle_promotion: row.reputation_per_battle_promotion(),
+                showcase_top_model_consideration_battles: row
+                    .showcase_top_model_consideration_battles(),
+                reputation_delay: row.property_reputation_delay(),
+            },
+        }
+    }
+}
+
+impl<'db> TypedDatabase<'db> {
+    /// Get the single row of `WorldConfig`
+    pub fn load_world_config(&self) -> Result<WorldConfig, WorldConfigError> {
+        let table = self
+            .world_config
+            .as_ref()
+            .ok_or(WorldConfigError::MissingTable)?;
+        let mut rows = table.row_iter();
+        match (rows.next(), rows.next()) {
+            (Some(row), None) => Ok(WorldConfig::from_row(&row)),
+            (None, _) => Err(WorldConfigError::RowCount(0)),
+            (Some(_), Some(_)) => Err(WorldConfigError::RowCount(2 + rows.count())),
+        }
+    }
+}
@@ -0,0 +1,301 @@
+//! # Zones
+//!
+//! `ZoneTable` has one row per zone. `ZoneLoadingTips`, `ZoneSummary`,
+//! `LUPZoneIDs` and `RocketLaunchpadControlComponent` refer to it by zone ID.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::TypedDatabase;
+
+/// A tip shown on the loading screen of a zone
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadingTip {
+    /// The ID of the tip (also used for the localized text)
+    pub id: i32,
+    /// The image shown with the tip
+    pub image_location: String,
+    /// The relative chance of picking this tip
+    pub weight: i32,
+    /// The only client version that shows this tip, if set
+    pub target_version: Option<String>,
+}
+
+impl LoadingTip {
+    /// Whether the tip may be shown to some client version
+    ///
+    /// Tips without a `targetVersion` are shown to all versions. Tips with a
+    /// weight below 1 are never shown.
+    pub fn is_shown_to(&self, version: Option<&str>) -> bool {
+        self.weight > 0
+            && match (&self.target_version, version) {
+                (None, _) => true,
+                (Some(target), Some(version)) => target == version,
+                (Some(_), None) => false,
+            }
+    }
+}
+
+/// A list of weighted loading tips
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct LoadingTips(pub Vec<LoadingTip>);
+
+impl LoadingTips {
+    /// Get the tips that may be shown to some client version
+    pub fn eligible<'a>(
+        &'a self,
+        version: Option<&'a str>,
+    ) -> impl Iterator<Item = &'a LoadingTip> + 'a {
+        self.0.iter().filter(move |tip| tip.is_shown_to(version))
+    }
+
+    /// Pick a tip with a number in `0..total_weight(version)`
+    pub fn pick_with(&self, version: Option<&str>, mut roll: u32) -> Option<&LoadingTip> {
+        for tip in self.0.iter().filter(|tip| tip.is_shown_to(version)) {
+            let weight = tip.weight as u32;
+            if roll < weight {
+                return Some(tip);
+            }
+            roll -= weight;
+        }
+        None
+    }
+
+    /// Get the sum of the weights of all eligible tips
+    pub fn total_weight(&self, version: Option<&str>) -> u32 {
+        self.eligible(version).map(|tip| tip.weight as u32).sum()
+    }
+
+    /// Pick a random tip, honouring the weights
+    #[cfg(feature = "rand")]
+    pub fn pick<R: rand::Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        version: Option<&str>,
+    ) -> Option<&LoadingTip> {
+        let total = self.total_weight(version);
+        if total == 0 {
+            return None;
+        }
+        self.pick_with(version, rng.gen_range(0..total))
+    }
+}
+
+/// A launchpad that flies to a zone
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneLaunchpad {
+    /// The LOT of the launchpad object
+    pub lot: i32,
+    /// The ID of the `RocketLaunchpadControlComponent`
+    pub component_id: i32,
+    /// The scene to spawn in
+    pub target_scene: Option<String>,
+}
+
+/// The data of a zone
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneInfo {
+    /// The ID of the zone
+    pub id: i32,
+    /// The internal name (`zoneName`)
+    pub name: String,
+    /// The display description
+    pub description: Option<String>,
+    /// The folder of the map files
+    pub map_folder: Option<String>,
+    /// The script of the zone
+    pub script_id: Option<i32>,
+    /// `population_soft_cap`
+    pub population_soft_cap: i32,
+    /// `population_hard_cap`
+    pub population_hard_cap: i32,
+    /// `ghostdistance_min`
+    pub ghost_distance_min: Option<f32>,
+    /// `ghostdistance`
+    pub ghost_distance: f32,
+    /// The object that controls the zone
+    pub zone_control_template: Option<i32>,
+    /// Whether pets can be used
+    pub pets_allowed: bool,
+    /// Whether mounts can be used
+    pub mounts_allowed: bool,
+    /// Whether players lose coins when smashed
+    pub player_lose_coins_on_death: bool,
+    /// Whether the zone is a LEGO Universe Partner zone (`LUPZoneIDs`)
+    pub is_lup: bool,
+    /// The `type` and `value` pairs from `ZoneSummary`
+    pub summary: Vec<(i32, Option<i32>)>,
+    /// The loading screen tips
+    pub loading_tips: LoadingTips,
+    /// The launchpads that fly to this zone
+    pub launchpads: Vec<ZoneLaunchpad>,
+}
+
+impl<'db> TypedDatabase<'db> {
+    /// Get the loading tips of a zone, ordered by ID
+    pub fn get_loading_tips(&self, zone_id: i32) -> LoadingTips {
+        let table = match &self.zone_loading_tips {
+            Some(table) => table,
+            None => return LoadingTips::default(),
+        };
+        let mut tips: Vec<LoadingTip> = table
+            .row_iter()
+            .filter(|row| row.zoneid() == zone_id)
+            .map(|row| LoadingTip {
+                id: row.id(),
+                image_location: row.imagelocation().decode().into_owned(),
+                weight: row.weight(),
+                target_version: row
+                    .target_version()
+                    .filter(|v| !v.is_empty())
+                    .map(|v| v.decode().into_owned()),
+            })
+            .collect();
+        tips.sort_by_key(|tip| tip.id);
+        LoadingTips(tips)
+    }
+
+    /// Get the launchpads that fly to a zone
+    pub fn get_zone_launchpads(&self, zone_id: i32) -> Vec<ZoneLaunchpad> {
+        let table = match &self.rocket_launchpad_control_component {
+            Some(table) => table,
+            None => return Vec::new(),
+        };
+        let scenes: BTreeMap<i32, Option<String>> = table
+            .row_iter()
+            .filter(|row| row.target_zone() == zone_id)
+            .map(|row| {
+                let scene = row.target_scene().map(|s| s.decode().into_owned());
+                (row.id(), scene)
+            })
+            .collect();
+        if scenes.is_empty() {
+            return Vec::new();
+        }
+        self.comp_reg
+            .row_iter()
+            .filter(|row| row.component_type() == 67)
+            .filter_map(|row| {
+                let target_scene = scenes.get(&row.component_id())?.clone();
+                Some(ZoneLaunchpad {
+                    lot: row.id(),
+                    component_id: row.component_id(),
+                    target_scene,
+                })
+            })
+            .collect()
+    }
+
+    /// Get the combined data of a zone
+    pub fn zone(&self, id: i32) -> Option<ZoneInfo> {
+        let row = self.zone_table.key_iter(id).next()?;
+        let is_lup = self
+            .lup_zone_ids
+            .as_ref()
+            .map_or(false, |t| t.key_iter(id).next().is_some());
+        let summary = self
+            .zone_summary
+            .as_ref()
+            .map(|t| t.key_iter(id).map(|s| (s.r#type(), s.value())).collect())
+            .unwrap_or_default();
+        Some(ZoneInfo {
+            id,
+            name: row.zone_name().decode().into_owned(),
+            description: row.display_description().map(|s| s.decode().into_owned()),
+            map_folder: row.map_folder().map(|s| s.decode().into_owned()),
+            script_id: row.script_id(),
+            population_soft_cap: row.population_soft_cap(),
+            population_hard_cap: row.population_hard_cap(),
+            ghost_distance_min: row.ghostdistance_min(),
+            ghost_distance: row.ghostdistance(),
+            zone_control_template: row.zone_control_template(),
+            pets_allowed: row.pets_allowed(),
+            mounts_allowed: row.mounts_allowed(),
+            player_lose_coins_on_death: row.player_lose_coins_on_death(),
+            is_lup,
+            summary,
+            loading_tips: self.get_loading_tips(id),
+            launchpads: self.get_zone_launchpads(id),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LoadingTip, LoadingTips};
+
+    fn tips() -> LoadingTips {
+        LoadingTips(vec![
+            LoadingTip {
+                id: 1,
+                image_location: String::new(),
+                weight: 2,
+                target_version: None,
+            },
+            LoadingTip {
+                id: 2,
+                image_location: String::new(),
+                weight: 0,
+                target_version: None,
+            },
+            LoadingTip {
+                id: 3,
+                image_location: String::new(),
+                weight: 3,
+                target_version: Some("1.10".to_owned()),
+            },
+            LoadingTip {
+                id: 4,
+                image_location: String::new(),
+                weight: 1,
+                target_version: None,
+            },
+        ])
+    }
+
+    fn pick(tips: &LoadingTips, version: Option<&str>, roll: u32) -> Option<i32> {
+        tips.pick_with(version, roll).map(|tip| tip.id)
+    }
+
+    #[test]
+    fn test_pick_with_boundaries() {
+        let tips = tips();
+        assert_eq!(tips.total_weight(Some("1.10")), 6);
+        let picks: Vec<_> = (0..6).map(|roll| pick(&tips, Some("1.10"), roll)).collect();
+        assert_eq!(
+            picks,
+            vec![Some(1), Some(1), Some(3), Some(3), Some(3), Some(4)]
+        );
+        assert_eq!(pick(&tips, Some("1.10"), 6), None);
+        assert_eq!(pick(&tips, Some("1.10"), u32::MAX), None);
+    }
+
+    #[test]
+    fn test_pick_with_version_filter() {
+        let tips = tips();
+        assert_eq!(tips.total_weight(None), 3);
+        assert_eq!(tips.total_weight(Some("1.9")), 3);
+        for &version in &[None, Some("1.9")] {
+            assert_eq!(pick(&tips, version, 1), Some(1));
+            assert_eq!(pick(&tips, version, 2), Some(4));
+            assert_eq!(pick(&tips, version, 3), None);
+        }
+    }
+
+    #[test]
+    fn test_zero_weight() {
+        let tips = tips();
+        assert!(tips.eligible(None).all(|tip| tip.id != 2));
+        let tips = LoadingTips(vec![LoadingTip {
+            weight: 0,
+            ..tips.0[0].clone()
+        }]);
+        assert_eq!(tips.total_weight(None), 0);
+        assert_eq!(pick(&tips, None, 0), None);
+    }
+}
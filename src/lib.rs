@@ -24,18 +24,24 @@ pub mod ext;
 
 use columns::{IconsColumn, MissionTasksColumn, MissionsColumn};
 use tables::{
-    ActivitiesTable, ActivityRewardsTable, ActivityTextTable, BehaviorParameterTable,
-    BehaviorTemplateTable, CollectibleComponentTable, ComponentsRegistryTable,
-    CurrencyDenominationsTable, DeletionRestrictionsTable, DestructibleComponentTable, EmotesTable,
-    IconsTable, InventoryComponentTable, ItemComponentTable, ItemSetSkillsTable, ItemSetsTable,
-    JetPackPadComponentTable, LootMatrixTable, LootTableTable, MissionEmailTable,
+    ActivitiesTable, ActivityRewardsTable, ActivityTextTable, BehaviorEffectTable,
+    BehaviorParameterTable, BehaviorTemplateNameTable, BehaviorTemplateTable, BuffDefinitionsTable,
+    BuffParametersTable, ChoiceBuildComponentTable, CollectibleComponentTable,
+    ComponentsRegistryTable, CurrencyDenominationsTable, CurrencyTableTable,
+    DeletionRestrictionsTable, DestructibleComponentTable, EmotesTable, FactionsTable, IconsTable,
+    InventoryComponentTable, ItemComponentTable, ItemSetSkillsTable, ItemSetsTable,
+    JetPackPadComponentTable, LanguageTypeTable, LevelProgressionLookupTable, LootMatrixTable,
+    LootTableTable, LupZoneIDsTable, MapItemTypesTable, MissionEmailTable,
     MissionNpcComponentTable, MissionTasksTable, MissionTextTable, MissionsTable, NpcIconsTable,
-    ObjectSkillsTable, ObjectsTable, PackageComponentTable, PlayerStatisticsTable,
-    PreconditionsTable, PropertyTemplateTable, RebuildComponentTable, RebuildSectionsTable,
-    RenderComponentTable, RewardCodesTable, RewardsTable, SkillBehaviorTable,
-    SmashableComponentTable, SpeedchatMenuTable, TamingBuildPuzzlesTable, UgBehaviorSoundsTable,
-    VendorComponentTable, WhatsCoolItemSpotlightTable, WhatsCoolNewsAndTipsTable,
-    ZoneLoadingTipsTable, ZoneTableTable,
+    ObjectSkillsTable, ObjectsTable, PackageComponentTable, PetAbilitiesTable, PetComponentTable,
+    PetNestComponentTable, PlayerStatisticsTable, PreconditionsTable, PropertyTemplateTable,
+    RailActivatorComponentTable, RebuildComponentTable, RebuildSectionsTable, RenderComponentTable,
+    ReputationRewardsTable, RewardCodesTable, RewardsTable, RocketLaunchpadControlComponentTable,
+    SkillBehaviorTable, SmashableChainIndexTable, SmashableChainTable, SmashableComponentTable,
+    SmashableElementsTable, SpeedchatMenuTable, TamingBuildPuzzlesTable, TextDescriptionTable,
+    TextLanguageTable, UgBehaviorSoundsTable, VendorComponentTable, WhatsCoolItemSpotlightTable,
+    WhatsCoolNewsAndTipsTable, WorldConfigTable, ZoneLoadingTipsTable, ZoneSummaryTable,
+    ZoneTableTable,
 };
 
 use self::ext::{Components, Mission, MissionTask};
@@ -156,22 +162,36 @@ pub struct TypedDatabase<'db> {
     pub activity_text: ActivityTextTable<'db>,
     /// ActivityRewards
     pub activity_rewards: Option<ActivityRewardsTable<'db>>,
+    /// BehaviorEffect
+    pub behavior_effect: Option<BehaviorEffectTable<'db>>,
     /// BehaviorParameter
     pub behavior_parameters: BehaviorParameterTable<'db>,
+    /// BehaviorTemplateName
+    pub behavior_template_names: Option<BehaviorTemplateNameTable<'db>>,
     /// BehaviorTemplate
     pub behavior_templates: BehaviorTemplateTable<'db>,
+    /// BuffDefinitions
+    pub buff_definitions: Option<BuffDefinitionsTable<'db>>,
+    /// BuffParameters
+    pub buff_parameters: Option<BuffParametersTable<'db>>,
+    /// ChoiceBuildComponent
+    pub choice_build_component: Option<ChoiceBuildComponentTable<'db>>,
     /// CollectibleComponent
     pub collectible_component: CollectibleComponentTable<'db>,
     /// ComponentRegistry
     pub comp_reg: ComponentsRegistryTable<'db>,
     /// CurrencyDenominations
     pub currency_denominations: CurrencyDenominationsTable<'db>,
+    /// CurrencyTable
+    pub currency_table: Option<CurrencyTableTable<'db>>,
     /// DeletionRestrictions
     pub deletion_restrictions: DeletionRestrictionsTable<'db>,
     /// DestructibleComponent
     pub destructible_component: DestructibleComponentTable<'db>,
     /// Emotes
     pub emotes: EmotesTable<'db>,
+    /// Factions
+    pub factions: Option<FactionsTable<'db>>,
     /// Icons
     pub icons: IconsTable<'db>,
     /// InventoryComponent
@@ -184,10 +204,18 @@ pub struct TypedDatabase<'db> {
     pub item_set_skills: ItemSetSkillsTable<'db>,
     /// JetPackPadComponent
     pub jet_pack_pad_component: Option<JetPackPadComponentTable<'db>>,
+    /// LanguageType
+    pub language_type: Option<LanguageTypeTable<'db>>,
+    /// LevelProgressionLookup
+    pub level_progression_lookup: Option<LevelProgressionLookupTable<'db>>,
     /// LootTable
     pub loot_table: LootTableTable<'db>,
     /// LootMatrix
     pub loot_matrix: LootMatrixTable<'db>,
+    /// LUPZoneIDs
+    pub lup_zone_ids: Option<LupZoneIDsTable<'db>>,
+    /// mapItemTypes
+    pub map_item_types: Option<MapItemTypesTable<'db>>,
     /// MissionEmail
     pub mission_email: Option<MissionEmailTable<'db>>,
     /// MissionNPCComponent
@@ -206,30 +234,52 @@ pub struct TypedDatabase<'db> {
     pub object_skills: ObjectSkillsTable<'db>,
     /// PackageComponent
     pub package_component: Option<PackageComponentTable<'db>>,
+    /// PetAbilities
+    pub pet_abilities: Option<PetAbilitiesTable<'db>>,
+    /// PetComponent
+    pub pet_component: Option<PetComponentTable<'db>>,
+    /// PetNestComponent
+    pub pet_nest_component: Option<PetNestComponentTable<'db>>,
     /// PlayerStatistics
     pub player_statistics: Option<PlayerStatisticsTable<'db>>,
     /// Preconditions
     pub preconditions: PreconditionsTable<'db>,
     /// PropertyTemplate
     pub property_template: PropertyTemplateTable<'db>,
+    /// RailActivatorComponent
+    pub rail_activator_component: Option<RailActivatorComponentTable<'db>>,
     /// RebuildComponent
     pub rebuild_component: RebuildComponentTable<'db>,
     /// RebuildSections
     pub rebuild_sections: Option<RebuildSectionsTable<'db>>,
+    /// ReputationRewards
+    pub reputation_rewards: Option<ReputationRewardsTable<'db>>,
     /// Rewards
     pub rewards: Option<RewardsTable<'db>>,
     /// RewardCodes
     pub reward_codes: Option<RewardCodesTable<'db>>,
     /// RenderComponent
     pub render_comp: RenderComponentTable<'db>,
+    /// RocketLaunchpadControlComponent
+    pub rocket_launchpad_control_component: Option<RocketLaunchpadControlComponentTable<'db>>,
     /// SkillBehavior
     pub skills: SkillBehaviorTable<'db>,
+    /// SmashableChain
+    pub smashable_chain: Option<SmashableChainTable<'db>>,
+    /// SmashableChainIndex
+    pub smashable_chain_index: Option<SmashableChainIndexTable<'db>>,
     /// SmashableComponent
     pub smashable_component: Option<SmashableComponentTable<'db>>,
+    /// SmashableElements
+    pub smashable_elements: Option<SmashableElementsTable<'db>>,
     /// SpeedchatMenu
     pub speedchat_menu: SpeedchatMenuTable<'db>,
     /// TamingBuildPuzzles
     pub taming_build_puzzles: TamingBuildPuzzlesTable<'db>,
+    /// TextDescription
+    pub text_description: Option<TextDescriptionTable<'db>>,
+    /// TextLanguage
+    pub text_language: Option<TextLanguageTable<'db>>,
     /// UGBehaviorSounds
     pub ug_behavior_sounds: Option<UgBehaviorSoundsTable<'db>>,
     /// VendorComponent
@@ -238,8 +288,12 @@ pub struct TypedDatabase<'db> {
     pub whats_cool_item_spotlight: Option<WhatsCoolItemSpotlightTable<'db>>,
     /// WhatsCoolNewsAndTips
     pub whats_cool_news_and_tips: Option<WhatsCoolNewsAndTipsTable<'db>>,
+    /// WorldConfig
+    pub world_config: Option<WorldConfigTable<'db>>,
     /// ZoneLoadingTips
     pub zone_loading_tips: Option<ZoneLoadingTipsTable<'db>>,
+    /// ZoneSummary
+    pub zone_summary: Option<ZoneSummaryTable<'db>>,
     /// ZoneTable
     pub zone_table: ZoneTableTable<'db>,
 }
@@ -255,21 +309,28 @@ impl<'a> TypedDatabase<'a> {
             activities: ActivitiesTable::of(tables).expect("Missing Table 'Activities'")?,
             activity_text: ActivityTextTable::of(tables).expect("Missing Table 'ActivityText'")?,
             activity_rewards: ActivityRewardsTable::of(tables).transpose()?,
+            behavior_effect: BehaviorEffectTable::of(tables).transpose()?,
             behavior_parameters: BehaviorParameterTable::of(tables)
                 .expect("Missing Table 'BehaviorParameter'")?,
+            behavior_template_names: BehaviorTemplateNameTable::of(tables).transpose()?,
             behavior_templates: BehaviorTemplateTable::of(tables)
                 .expect("Missing Table 'BehaviorTemplate'")?,
+            buff_definitions: BuffDefinitionsTable::of(tables).transpose()?,
+            buff_parameters: BuffParametersTable::of(tables).transpose()?,
+            choice_build_component: ChoiceBuildComponentTable::of(tables).transpose()?,
             collectible_component: CollectibleComponentTable::of(tables)
                 .expect("Missing Table 'CollectibleComponent'")?,
             comp_reg: ComponentsRegistryTable::of(tables)
                 .expect("Missing Table 'ComponentsRegistry'")?,
             currency_denominations: CurrencyDenominationsTable::of(tables)
                 .expect("Missing Table 'CurrencyDenominations'")?,
+            currency_table: CurrencyTableTable::of(tables).transpose()?,
             deletion_restrictions: DeletionRestrictionsTable::of(tables)
                 .expect("Missing Table 'DeletionRestrictions'")?,
             destructible_component: DestructibleComponentTable::of(tables)
                 .expect("Missing Table 'DestructibleComponent'")?,
             emotes: EmotesTable::of(tables).expect("Missing Table 'Emotes'")?,
+            factions: FactionsTable::of(tables).transpose()?,
             icons: IconsTable::of(tables).expect("Missing Table 'Icons'")?,
             inventory_component: InventoryComponentTable::of(tables)
                 .expect("Missing Table 'InventoryComponent'")?,
@@ -279,8 +340,12 @@ impl<'a> TypedDatabase<'a> {
             item_set_skills: ItemSetSkillsTable::of(tables)
                 .expect("Missing Table 'ItemSetSkills'")?,
             jet_pack_pad_component: JetPackPadComponentTable::of(tables).transpose()?,
+            language_type: LanguageTypeTable::of(tables).transpose()?,
+            level_progression_lookup: LevelProgressionLookupTable::of(tables).transpose()?,
             loot_matrix: LootMatrixTable::of(tables).expect("Missing Table 'LootMatrix'")?,
             loot_table: LootTableTable::of(tables).expect("Missing Table 'LootTable'")?,
+            lup_zone_ids: LupZoneIDsTable::of(tables).transpose()?,
+            map_item_types: MapItemTypesTable::of(tables).transpose()?,
             mission_email: MissionEmailTable::of(tables).transpose()?,
             mission_npc_component: MissionNpcComponentTable::of(tables).transpose()?,
             mission_tasks: MissionTasksTable::of(tables).expect("Missing Table 'MissionTasks'")?,
@@ -290,11 +355,16 @@ impl<'a> TypedDatabase<'a> {
             objects: ObjectsTable::of(tables).expect("Missing Table 'Objects'")?,
             object_skills: ObjectSkillsTable::of(tables).expect("Missing Table 'ObjectSkills'")?,
             package_component: PackageComponentTable::of(tables).transpose()?,
+            pet_abilities: PetAbilitiesTable::of(tables).transpose()?,
+            pet_component: PetComponentTable::of(tables).transpose()?,
+            pet_nest_component: PetNestComponentTable::of(tables).transpose()?,
             player_statistics: PlayerStatisticsTable::of(tables).transpose()?,
             preconditions: PreconditionsTable::of(tables)
                 .expect("Missing Table 'Preconditions'")?,
             property_template: PropertyTemplateTable::of(tables)
                 .expect("Missing Table 'PropertyTemplate'")?,
+            rail_activator_component: RailActivatorComponentTable::of(tables).transpose()?,
+            reputation_rewards: ReputationRewardsTable::of(tables).transpose()?,
             rewards: RewardsTable::of(tables).transpose()?,
             reward_codes: RewardCodesTable::of(tables).transpose()?,
             rebuild_component: RebuildComponentTable::of(tables)
@@ -302,17 +372,26 @@ impl<'a> TypedDatabase<'a> {
             rebuild_sections: RebuildSectionsTable::of(tables).transpose()?,
             render_comp: RenderComponentTable::of(tables)
                 .expect("Missing Table 'RenderComponent'")?,
+            rocket_launchpad_control_component: RocketLaunchpadControlComponentTable::of(tables)
+                .transpose()?,
             skills: SkillBehaviorTable::of(tables).expect("Missing Table 'SkillBehavior'")?,
+            smashable_chain: SmashableChainTable::of(tables).transpose()?,
+            smashable_chain_index: SmashableChainIndexTable::of(tables).transpose()?,
             smashable_component: SmashableComponentTable::of(tables).transpose()?,
+            smashable_elements: SmashableElementsTable::of(tables).transpose()?,
             speedchat_menu: SpeedchatMenuTable::of(tables)
                 .expect("Missing Table 'SpeedchatMenu'")?,
             taming_build_puzzles: TamingBuildPuzzlesTable::of(tables)
                 .expect("Missing Table 'TamingBuildPuzzles'")?,
+            text_description: TextDescriptionTable::of(tables).transpose()?,
+            text_language: TextLanguageTable::of(tables).transpose()?,
             ug_behavior_sounds: UgBehaviorSoundsTable::of(tables).transpose()?,
             vendor_component: VendorComponentTable::of(tables).transpose()?,
             whats_cool_item_spotlight: WhatsCoolItemSpotlightTable::of(tables).transpose()?,
             whats_cool_news_and_tips: WhatsCoolNewsAndTipsTable::of(tables).transpose()?,
+            world_config: WorldConfigTable::of(tables).transpose()?,
             zone_loading_tips: ZoneLoadingTipsTable::of(tables).transpose()?,
+            zone_summary: ZoneSummaryTable::of(tables).transpose()?,
             zone_table: ZoneTableTable::of(tables).expect("Missing Table 'ZoneTable'")?,
         })
     }
@@ -498,8 +577,16 @@ impl<'a> TypedDatabase<'a> {
                 let component_type = fields.next().unwrap();
                 let component_id = fields.next().unwrap();
 
-                if let Value::Integer(2) = component_type {
-                    comp.render = component_id.into_opt_integer();
+                match component_type {
+                    Value::Integer(2) => comp.render = component_id.into_opt_integer(),
+                    Value::Integer(7) => comp.destructible = component_id.into_opt_integer(),
+                    Value::Integer(11) => comp.item = component_id.into_opt_integer(),
+                    Value::Integer(16) => comp.vendor = component_id.into_opt_integer(),
+                    Value::Integer(17) => comp.inventory = component_id.into_opt_integer(),
+                    Value::Integer(26) => comp.pet = component_id.into_opt_integer(),
+                    Value::Integer(48) => comp.quickbuild = component_id.into_opt_integer(),
+                    Value::Integer(52) => comp.choice_build = component_id.into_opt_integer(),
+                    _ => {}
                 }
             }
         }